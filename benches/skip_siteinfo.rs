@@ -0,0 +1,71 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+//! Compares the time to reach the first page of a dump with a large
+//! `siteinfo` element by parsing the XML events before it and by scanning
+//! for it with `ParserBuilder::parse_skipping_siteinfo`.
+//!
+//! Run with `cargo bench --features memchr`.
+
+use parse_mediawiki_dump::{NamespaceId, ParserBuilder};
+use std::time::{Duration, Instant};
+
+const NAMESPACES: usize = 200_000;
+const RUNS: usize = 11;
+
+fn main() {
+    let mut dump = String::from(
+        "<mediawiki xmlns=\"http://www.mediawiki.org/xml/export-0.10/\">\
+         <siteinfo><sitename>Wikipedia</sitename><namespaces>",
+    );
+    for id in 0..NAMESPACES {
+        dump.push_str(&format!(
+            "<namespace key=\"{}\" case=\"first-letter\">Namespace {}</namespace>",
+            id, id
+        ));
+    }
+    dump.push_str(
+        "</namespaces></siteinfo>\
+         <page><ns>0</ns><title>alpha</title><revision><text>beta</text></revision></page>\
+         </mediawiki>",
+    );
+    println!(
+        "siteinfo with {} namespaces, {} bytes",
+        NAMESPACES,
+        dump.len()
+    );
+    let events = median(|| {
+        let page = ParserBuilder::new()
+            .parse(dump.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(page.title, "alpha");
+    });
+    let scan = median(|| {
+        let page = ParserBuilder::new()
+            .parse_skipping_siteinfo::<_, NamespaceId>(dump.as_bytes())
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(page.title, "alpha");
+    });
+    println!("parse:                   {:?}", events);
+    println!("parse_skipping_siteinfo: {:?}", scan);
+    println!("speedup: {:.1}x", events.as_secs_f64() / scan.as_secs_f64());
+}
+
+/// Returns the median time of running `f`.
+fn median(mut f: impl FnMut()) -> Duration {
+    let mut times = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect::<Vec<_>>();
+    times.sort();
+    times[RUNS / 2]
+}
@@ -0,0 +1,28 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+fn main() {
+    let mut args = std::env::args();
+    if args.len() != 2 {
+        eprintln!("invalid use");
+        std::process::exit(1);
+    }
+    let path = args.nth(1).unwrap();
+    let parser = match parse_mediawiki_dump::parse_gz(&path) {
+        Err(error) => {
+            eprintln!("Failed to open input file: {}", error);
+            std::process::exit(1);
+        }
+        Ok(parser) => parser,
+    };
+    for result in parser {
+        match result {
+            Err(error) => {
+                eprintln!("Error: {}", error);
+                std::process::exit(1);
+            }
+            Ok(page) => eprintln!("{:#?}", page),
+        }
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{Error, FromNamespaceId, Page, Parser};
+use bumpalo::Bump;
+use std::io::BufRead;
+
+/**
+A parsed page whose strings are allocated in an arena, returned by
+[`Parser::next_in`]. Only available with the `bumpalo` feature.
+
+The fields are the same as the fields of [`Page`] with the same names,
+borrowed from the arena instead of owned, so the page can't outlive the
+next reset of the arena. The fields of [`Page`] that are only present when
+enabled with an option of the parser are left out, except the span of the
+text.
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct ArenaPage<'b, N> {
+    /// The title of the page with the namespace prefix.
+    pub title: &'b str,
+
+    /// The namespace of the page.
+    pub namespace: N,
+
+    /// The id of the page if any.
+    pub page_id: Option<u64>,
+
+    /// The format of the revision if any.
+    pub format: Option<&'b str>,
+
+    /// The model of the revision if any.
+    pub model: Option<&'b str>,
+
+    /// The text of the revision.
+    pub text: &'b str,
+
+    /// The redirect target if any.
+    pub redirect_title: Option<&'b str>,
+
+    /// The SHA-1 of the text of the revision if any.
+    pub sha1: Option<&'b str>,
+
+    /// The byte offsets of the start and end of the content of the `text`
+    /// element in the stream, if enabled with
+    /// [`ParserBuilder::text_spans`](crate::ParserBuilder::text_spans).
+    pub text_span: Option<(usize, usize)>,
+}
+
+impl<R: BufRead, N: FromNamespaceId> Parser<R, N> {
+    /**
+    Parses the next page like [`Iterator::next`], allocating its strings in
+    `bump`. Only available with the `bumpalo` feature.
+
+    This is a copy: the strings are parsed onto the heap as usual, copied
+    into the arena and freed before this method returns, and the parser
+    doesn't keep a buffer for the text. So the memory held from one page to
+    the next is the arena's, and resetting the arena between pages keeps the
+    memory use of a long-running process bounded. While a page is parsed,
+    its text is allocated twice, once on the heap and once in the arena, so
+    the peak memory use for a page is about twice the length of its text.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text>beta</text></revision></page>
+        <page><ns>0</ns><title>gamma</title><revision><text>delta</text></revision></page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    let mut bump = bumpalo::Bump::new();
+    let mut titles = vec![];
+    loop {
+        match parser.next_in(&bump) {
+            None => break,
+            Some(page) => titles.push(page.unwrap().title.to_string()),
+        }
+        bump.reset();
+    }
+    assert_eq!(titles, ["alpha", "gamma"]);
+    ```
+    */
+    pub fn next_in<'b>(
+        &mut self,
+        bump: &'b Bump,
+    ) -> Option<Result<ArenaPage<'b, N>, Error>> {
+        self.next().map(|result| {
+            result.map(|page| {
+                let Page {
+                    title,
+                    namespace,
+                    page_id,
+                    format,
+                    model,
+                    redirect_title,
+                    sha1,
+                    text,
+                    text_span,
+                    ..
+                } = page;
+                let alloc = |value: Option<String>| {
+                    value.map(|value| &*bump.alloc_str(&value))
+                };
+                ArenaPage {
+                    title: bump.alloc_str(&title),
+                    namespace,
+                    page_id,
+                    format: alloc(format),
+                    model: alloc(model),
+                    text: bump.alloc_str(&text),
+                    redirect_title: alloc(redirect_title),
+                    sha1: alloc(sha1),
+                    text_span,
+                }
+            })
+        })
+    }
+}
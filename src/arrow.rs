@@ -0,0 +1,199 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{Chunks, Error, NamespaceId, Page, Parser};
+use arrow_array::{
+    types::Int32Type, ArrayRef, DictionaryArray, Int32Array, RecordBatch,
+    StringArray, UInt64Array,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use std::{io::BufRead, sync::Arc};
+
+/// How the text of the pages is given in a [`RecordBatch`] made by
+/// [`RecordBatches`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextColumn {
+    /// The text is in the column `text` of type `Dictionary(Int32, Utf8)`,
+    /// so that pages with the same text share it, such as redirects.
+    Dictionary,
+
+    /// The text is not kept. Instead the column `text_offset` of type
+    /// `UInt64` has the byte offset of the text in the stream, which
+    /// together with the column `text_len` allows reading it from the
+    /// stream later, as enabled with
+    /// [`ParserBuilder::text_spans`](crate::ParserBuilder::text_spans).
+    Span,
+}
+
+/**
+Iterator over pages in Arrow [`RecordBatch`]es.
+
+Created by [`Parser::record_batches`] and [`parse_record_batches`]. Only
+available with the `arrow` feature.
+
+Every batch has the following columns, followed by the columns of the
+text given by [`TextColumn`]:
+
+- `page_id` of type `UInt64`, null if the page has no `id` element.
+- `namespace` of type `Int32`.
+- `title` of type `Utf8`.
+- `redirect_title` of type `Utf8`, null if the page is not a redirect.
+- `text_len` of type `UInt64`, the length of the text in bytes.
+*/
+pub struct RecordBatches<R: BufRead> {
+    chunks: Chunks<R, NamespaceId>,
+    schema: SchemaRef,
+    text: TextColumn,
+}
+
+impl<R: BufRead> Parser<R, NamespaceId> {
+    /**
+    Converts the parser into an iterator over Arrow [`RecordBatch`]es of up
+    to `batch_size` pages, with the text given as chosen by `text`. Only
+    available with the `arrow` feature.
+
+    The batches are made as by [`Parser::chunks`], so errors are reported
+    the same way. See [`RecordBatches`] for the columns.
+
+    # Panics
+
+    Panics if `batch_size` is 0.
+    */
+    pub fn record_batches(
+        mut self,
+        batch_size: usize,
+        text: TextColumn,
+    ) -> RecordBatches<R> {
+        if text == TextColumn::Span {
+            self.options.text_spans = true;
+        }
+        let mut fields = vec![
+            Field::new("page_id", DataType::UInt64, true),
+            Field::new("namespace", DataType::Int32, false),
+            Field::new("title", DataType::Utf8, false),
+            Field::new("redirect_title", DataType::Utf8, true),
+            Field::new("text_len", DataType::UInt64, false),
+        ];
+        fields.push(match text {
+            TextColumn::Dictionary => Field::new_dictionary(
+                "text",
+                DataType::Int32,
+                DataType::Utf8,
+                false,
+            ),
+            TextColumn::Span => {
+                Field::new("text_offset", DataType::UInt64, true)
+            }
+        });
+        RecordBatches {
+            chunks: self.chunks(batch_size),
+            schema: Arc::new(Schema::new(fields)),
+            text,
+        }
+    }
+}
+
+impl<R: BufRead> RecordBatches<R> {
+    /// Returns the schema of the batches.
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn batch(&self, pages: Vec<Page<NamespaceId>>) -> RecordBatch {
+        let mut columns: Vec<ArrayRef> = vec![
+            Arc::new(
+                pages
+                    .iter()
+                    .map(|page| page.page_id)
+                    .collect::<UInt64Array>(),
+            ),
+            Arc::new(
+                pages
+                    .iter()
+                    .map(|page| page.namespace.into_inner())
+                    .collect::<Int32Array>(),
+            ),
+            Arc::new(
+                pages
+                    .iter()
+                    .map(|page| Some(&*page.title))
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(
+                pages
+                    .iter()
+                    .map(|page| page.redirect_title.as_deref())
+                    .collect::<StringArray>(),
+            ),
+            Arc::new(
+                pages
+                    .iter()
+                    .map(|page| Some(text_len(page)))
+                    .collect::<UInt64Array>(),
+            ),
+        ];
+        columns.push(match self.text {
+            TextColumn::Dictionary => Arc::new(
+                pages
+                    .iter()
+                    .map(|page| &*page.text)
+                    .collect::<DictionaryArray<Int32Type>>(),
+            ),
+            TextColumn::Span => Arc::new(
+                pages
+                    .iter()
+                    .map(|page| page.text_span.map(|(start, _)| start as u64))
+                    .collect::<UInt64Array>(),
+            ),
+        });
+        RecordBatch::try_new(self.schema.clone(), columns)
+            .expect("the columns match the schema")
+    }
+}
+
+/// Returns the length of the text of the page in bytes, which is the length
+/// of the span if the text was not kept.
+fn text_len(page: &Page<NamespaceId>) -> u64 {
+    match page.text_span {
+        Some((start, end)) => (end - start) as u64,
+        None => page.text.len() as u64,
+    }
+}
+
+impl<R: BufRead> Iterator for RecordBatches<R> {
+    type Item = Result<RecordBatch, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pages = self.chunks.next()?;
+        Some(pages.map(|pages| self.batch(pages)))
+    }
+}
+
+/**
+Creates an iterator over the pages in a stream in Arrow [`RecordBatch`]es
+of up to `batch_size` pages, with the text in a dictionary-encoded column.
+See [`Parser::record_batches`]. Only available with the `arrow` feature.
+
+```rust
+let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page><ns>0</ns><title>alpha</title><revision><text>beta</text></revision></page>
+    <page><ns>0</ns><title>gamma</title><revision><text>delta</text></revision></page>
+    <page><ns>1</ns><title>Talk:alpha</title><revision><text/></revision></page>
+</mediawiki>"#;
+let rows = parse_mediawiki_dump::parse_record_batches(dump.as_bytes(), 2)
+    .map(|batch| batch.unwrap().num_rows())
+    .collect::<Vec<_>>();
+assert_eq!(rows, [2, 1]);
+```
+
+# Panics
+
+Panics if `batch_size` is 0.
+*/
+pub fn parse_record_batches<R: BufRead>(
+    source: R,
+    batch_size: usize,
+) -> RecordBatches<R> {
+    crate::parse(source).record_batches(batch_size, TextColumn::Dictionary)
+}
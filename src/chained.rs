@@ -0,0 +1,110 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{NamespaceId, Parser, ParserBuilder};
+use std::io::{BufRead, Read, Result};
+
+/**
+Reader that reads several streams one after another as one stream.
+
+The streams are taken from an iterator only when the previous stream has
+been read to the end, so for example files can be opened one at a time.
+Combined with [`ParserBuilder::multiple_roots`], this parses a dump split
+into several files as if it were one file.
+*/
+pub struct ChainedReader<I: Iterator> {
+    current: Option<I::Item>,
+    streams: I,
+}
+
+impl<I> ChainedReader<I>
+where
+    I: Iterator,
+    I::Item: BufRead,
+{
+    /// Creates a reader that reads the given streams in order.
+    pub fn new(streams: impl IntoIterator<IntoIter = I>) -> Self {
+        let mut streams = streams.into_iter();
+        Self {
+            current: streams.next(),
+            streams,
+        }
+    }
+}
+
+impl<I> Read for ChainedReader<I>
+where
+    I: Iterator,
+    I::Item: BufRead,
+{
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        let available = self.fill_buf()?;
+        let length = available.len().min(buffer.len());
+        buffer[..length].copy_from_slice(&available[..length]);
+        self.consume(length);
+        Ok(length)
+    }
+}
+
+impl<I> BufRead for ChainedReader<I>
+where
+    I: Iterator,
+    I::Item: BufRead,
+{
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        while let Some(current) = &mut self.current {
+            if !current.fill_buf()?.is_empty() {
+                break;
+            }
+            self.current = self.streams.next();
+        }
+        match &mut self.current {
+            None => Ok(&[]),
+            Some(current) => current.fill_buf(),
+        }
+    }
+
+    fn consume(&mut self, amount: usize) {
+        if let Some(current) = &mut self.current {
+            current.consume(amount);
+        }
+    }
+}
+
+/**
+Creates a parser for several streams read one after another, each
+containing a dump, such as the parts of a dump split into several files.
+
+Equivalent to parsing a [`ChainedReader`] of the streams with
+[`ParserBuilder::multiple_roots`] enabled.
+
+```rust
+let first = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <siteinfo><sitename>Wikipedia</sitename></siteinfo>
+    <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+</mediawiki>"#;
+let second = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page><ns>0</ns><title>beta</title><revision><text/></revision></page>
+</mediawiki>"#;
+let titles = parse_mediawiki_dump::parse_chained(vec![
+    first.as_bytes(),
+    second.as_bytes(),
+])
+.map(|page| page.map(|page| page.title))
+.collect::<Result<Vec<_>, _>>()
+.unwrap();
+assert_eq!(titles, ["alpha", "beta"]);
+```
+*/
+pub fn parse_chained<S>(
+    streams: S,
+) -> Parser<ChainedReader<S::IntoIter>, NamespaceId>
+where
+    S: IntoIterator,
+    S::Item: BufRead,
+{
+    ParserBuilder::new()
+        .multiple_roots(true)
+        .parse(ChainedReader::new(streams))
+}
@@ -0,0 +1,57 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{parse_with_namespace, Error, FromNamespaceId, Page};
+use std::io::BufRead;
+use tokio::sync::mpsc::{channel, Receiver};
+
+/**
+Parses a stream on a blocking task of the Tokio runtime and sends the pages
+to a channel holding up to `capacity` pages.
+
+The parser reads the stream synchronously, which would block the thread of
+an asynchronous task, so it runs in [`tokio::task::spawn_blocking`] instead.
+When the channel is full, the parser waits until the receiver has taken
+a page, so a slow receiver doesn't cause the whole dump to be buffered in
+memory. Parsing stops after the last page or when the receiver is dropped.
+
+Only available with the `tokio` feature.
+
+# Panics
+
+Panics if called outside the context of a Tokio runtime or if `capacity` is 0.
+
+```rust
+# tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+</mediawiki>"#;
+let mut receiver = parse_mediawiki_dump::parse_into_channel::<
+    _,
+    parse_mediawiki_dump::NamespaceId,
+>(dump.as_bytes(), 16);
+while let Some(page) = receiver.recv().await {
+    assert_eq!(page.unwrap().title, "alpha");
+}
+# });
+```
+*/
+pub fn parse_into_channel<R, N>(
+    source: R,
+    capacity: usize,
+) -> Receiver<Result<Page<N>, Error>>
+where
+    R: BufRead + Send + 'static,
+    N: FromNamespaceId + Send + 'static,
+{
+    let (sender, receiver) = channel(capacity);
+    tokio::task::spawn_blocking(move || {
+        for page in parse_with_namespace(source) {
+            if sender.blocking_send(page).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
@@ -0,0 +1,151 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{Error, FromNamespaceId, Page, Parser};
+use std::io::BufRead;
+
+/// Iterator over batches of pages.
+///
+/// Created by [`Parser::chunks`].
+pub struct Chunks<R: BufRead, N> {
+    error: Option<Error>,
+    finished: bool,
+    parser: Parser<R, N>,
+    size: usize,
+}
+
+impl<R: BufRead, N: FromNamespaceId> Parser<R, N> {
+    /**
+    Converts the parser into an iterator over batches of up to `size` pages.
+
+    Every batch has exactly `size` pages except the last one and any batch
+    cut short by an error. If an error occurs, the pages read before it are
+    yielded as a batch first, and the error is yielded on the following call,
+    after which parsing continues as the parser would.
+
+    # Panics
+
+    Panics if `size` is 0.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+        <page><ns>0</ns><title>beta</title><revision><text/></revision></page>
+        <page><ns>0</ns><title>gamma</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let sizes = parse_mediawiki_dump::parse(dump.as_bytes())
+        .chunks(2)
+        .map(|batch| batch.unwrap().len())
+        .collect::<Vec<_>>();
+    assert_eq!(sizes, [2, 1]);
+    ```
+    */
+    pub fn chunks(self, size: usize) -> Chunks<R, N> {
+        assert!(size != 0, "chunk size must be non-zero");
+        Chunks {
+            error: None,
+            finished: false,
+            parser: self,
+            size,
+        }
+    }
+
+    /**
+    Reads up to `max` pages into `sink`, replacing its content, and returns
+    the number of pages read, which is 0 at the end of the dump.
+
+    Unlike [`Parser::chunks`], this allows reusing the same vector for
+    every batch. The texts of the pages in `sink` are reused for the texts
+    of the new pages as with [`Parser::next_text_into`], so once the pages
+    have been filled with long enough texts, reading a batch doesn't
+    allocate texts. If an error occurs, it is returned with `sink` holding
+    the pages read before it.
+
+    # Panics
+
+    Panics if `max` is 0.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text>beta</text></revision></page>
+        <page><ns>0</ns><title>gamma</title><revision><text>delta</text></revision></page>
+        <page><ns>0</ns><title>epsilon</title><revision><text>zeta</text></revision></page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    let mut pages = vec![];
+    let mut titles = vec![];
+    while parser.next_batch_into(&mut pages, 2).unwrap() > 0 {
+        titles.push(pages.iter().map(|page| page.title.clone()).collect::<Vec<_>>());
+    }
+    assert_eq!(titles, [vec!["alpha", "gamma"], vec!["epsilon"]]);
+    ```
+    */
+    pub fn next_batch_into(
+        &mut self,
+        sink: &mut Vec<Page<N>>,
+        max: usize,
+    ) -> Result<usize, Error> {
+        assert!(max != 0, "batch size must be non-zero");
+        let mut count = 0;
+        let result = loop {
+            if count == max {
+                break Ok(count);
+            }
+            let mut text = match sink.get_mut(count) {
+                None => String::new(),
+                Some(page) => std::mem::take(&mut page.text),
+            };
+            text.clear();
+            match self.next_text_into(&mut text) {
+                None => break Ok(count),
+                Some(Err(error)) => break Err(error),
+                Some(Ok(mut page)) => {
+                    page.text = text;
+                    match sink.get_mut(count) {
+                        None => sink.push(page),
+                        Some(slot) => *slot = page,
+                    }
+                    count += 1;
+                }
+            }
+        };
+        sink.truncate(count);
+        result
+    }
+}
+
+impl<R: BufRead, N: FromNamespaceId> Iterator for Chunks<R, N> {
+    type Item = Result<Vec<Page<N>>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.error.take() {
+            return Some(Err(error));
+        }
+        if self.finished {
+            return None;
+        }
+        let mut batch = Vec::with_capacity(self.size);
+        while batch.len() < self.size {
+            match self.parser.next() {
+                None => {
+                    self.finished = true;
+                    break;
+                }
+                Some(Ok(page)) => batch.push(page),
+                Some(Err(error)) => {
+                    if batch.is_empty() {
+                        return Some(Err(error));
+                    }
+                    self.error = Some(error);
+                    break;
+                }
+            }
+        }
+        if batch.is_empty() {
+            None
+        } else {
+            Some(Ok(batch))
+        }
+    }
+}
@@ -0,0 +1,249 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::Page;
+use std::{convert::Infallible, fmt, str::FromStr};
+
+/// Defines an enum of the known values of a string with a variant for
+/// other values, converting to and from the string.
+macro_rules! content_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// Any other value, as written in the dump.
+            Other(String),
+        }
+
+        impl $name {
+            /// Returns the string representing the value in the dump.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::Other(value) => value,
+                }
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                Ok(match value {
+                    $($value => $name::$variant,)*
+                    _ => $name::Other(value.to_string()),
+                })
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str(self.as_str())
+            }
+        }
+    };
+}
+
+content_enum! {
+    /**
+    The [content model](https://www.mediawiki.org/wiki/Content_handlers)
+    of a revision, as given by the `model` element.
+
+    Converts to and from the string used by MediaWiki, with values not
+    known to this crate kept in [`ContentModel::Other`].
+
+    ```rust
+    use parse_mediawiki_dump::ContentModel;
+    let model = "wikitext".parse::<ContentModel>().unwrap();
+    assert_eq!(model, ContentModel::Wikitext);
+    assert_eq!(model.to_string(), "wikitext");
+    ```
+    */
+    pub enum ContentModel {
+        /// Cascading style sheets, `css`.
+        Css = "css",
+        /// A board of the Flow extension, `flow-board`.
+        FlowBoard = "flow-board",
+        /// JavaScript, `javascript`.
+        JavaScript = "javascript",
+        /// JSON, `json`.
+        Json = "json",
+        /// A page of the Proofread Page extension, `proofread-page`.
+        ProofreadPage = "proofread-page",
+        /// Sanitized cascading style sheets of the TemplateStyles extension,
+        /// `sanitized-css`.
+        SanitizedCss = "sanitized-css",
+        /// A Lua module of the Scribunto extension, `Scribunto`.
+        Scribunto = "Scribunto",
+        /// Plain text, `text`.
+        Text = "text",
+        /// An item of the Wikibase extension, `wikibase-item`.
+        WikibaseItem = "wikibase-item",
+        /// A lexeme of the WikibaseLexeme extension, `wikibase-lexeme`.
+        WikibaseLexeme = "wikibase-lexeme",
+        /// A property of the Wikibase extension, `wikibase-property`.
+        WikibaseProperty = "wikibase-property",
+        /// Wiki text, `wikitext`.
+        Wikitext = "wikitext",
+    }
+}
+
+content_enum! {
+    /**
+    The serialization format of a revision, as given by the `format`
+    element.
+
+    Converts to and from the MIME type used by MediaWiki, with values not
+    known to this crate kept in [`ContentFormat::Other`].
+
+    ```rust
+    use parse_mediawiki_dump::ContentFormat;
+    let format = "text/x-wiki".parse::<ContentFormat>().unwrap();
+    assert_eq!(format, ContentFormat::Wikitext);
+    assert_eq!(format.to_string(), "text/x-wiki");
+    ```
+    */
+    pub enum ContentFormat {
+        /// Cascading style sheets, `text/css`.
+        Css = "text/css",
+        /// HTML, `text/html`.
+        Html = "text/html",
+        /// JavaScript, `text/javascript`.
+        JavaScript = "text/javascript",
+        /// JSON, `application/json`.
+        Json = "application/json",
+        /// Arbitrary binary data, `application/octet-stream`.
+        OctetStream = "application/octet-stream",
+        /// Serialized PHP, `application/vnd.php.serialized`.
+        Serialized = "application/vnd.php.serialized",
+        /// Plain text, `text/plain`.
+        Text = "text/plain",
+        /// Wiki text, `text/x-wiki`.
+        Wikitext = "text/x-wiki",
+        /// XML, `application/xml`.
+        Xml = "application/xml",
+    }
+}
+
+impl ContentModel {
+    /**
+    Returns whether the model is a content model of the Wikibase extension
+    or its extensions, whose text is a JSON entity, as in the dumps of
+    Wikidata.
+
+    This is the case for the models starting with `wikibase-`, such as
+    [`ContentModel::WikibaseItem`] and `wikibase-mediainfo`.
+
+    ```rust
+    use parse_mediawiki_dump::ContentModel;
+    assert!(ContentModel::WikibaseProperty.is_wikibase());
+    assert!(ContentModel::Other("wikibase-mediainfo".to_string()).is_wikibase());
+    assert!(!ContentModel::Json.is_wikibase());
+    ```
+    */
+    pub fn is_wikibase(&self) -> bool {
+        self.as_str().starts_with("wikibase-")
+    }
+}
+
+impl ContentFormat {
+    /**
+    Returns whether the format is binary rather than text, so that the text
+    of a revision in this format is not meant to be read as text.
+
+    This is the case for [`ContentFormat::OctetStream`] and for image, audio
+    and video MIME types.
+
+    ```rust
+    use parse_mediawiki_dump::ContentFormat;
+    assert!(ContentFormat::OctetStream.is_binary());
+    assert!(ContentFormat::Other("image/png".to_string()).is_binary());
+    assert!(!ContentFormat::Json.is_binary());
+    ```
+    */
+    pub fn is_binary(&self) -> bool {
+        match self {
+            ContentFormat::OctetStream => true,
+            ContentFormat::Other(format) => ["image/", "audio/", "video/"]
+                .iter()
+                .any(|prefix| format.starts_with(prefix)),
+            _ => false,
+        }
+    }
+}
+
+impl<N> Page<N> {
+    /**
+    Returns whether the revision has binary content according to its format,
+    as determined by [`ContentFormat::is_binary`].
+
+    No content model defined by MediaWiki or its common extensions implies
+    binary content by itself, so the model is not considered. A revision
+    without a `format` element is assumed to be text.
+    */
+    pub fn is_binary_content(&self) -> bool {
+        match self.content_format() {
+            Some(format) => format.is_binary(),
+            None => false,
+        }
+    }
+
+    /// Returns the content model of the revision if any, parsed from the
+    /// `model` field.
+    pub fn content_model(&self) -> Option<ContentModel> {
+        self.model.as_deref().map(|model| match model.parse() {
+            Ok(model) => model,
+            Err(error) => match error {},
+        })
+    }
+
+    /**
+    Parses the text of the page as JSON if its content model is a Wikibase
+    content model, as determined by [`ContentModel::is_wikibase`]. Only
+    available with the `serde_json` feature.
+
+    Returns `None` if the page has another model or no model, or if the
+    text is not valid JSON.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page>
+            <ns>0</ns>
+            <title>Q42</title>
+            <revision>
+                <model>wikibase-item</model>
+                <format>application/json</format>
+                <text>{"type":"item","id":"Q42"}</text>
+            </revision>
+        </page>
+    </mediawiki>"#;
+    let page = parse_mediawiki_dump::parse(dump.as_bytes()).next().unwrap().unwrap();
+    assert_eq!(page.as_json().unwrap()["id"], "Q42");
+    ```
+    */
+    #[cfg(feature = "serde_json")]
+    pub fn as_json(&self) -> Option<serde_json::Value> {
+        match self.content_model() {
+            Some(model) if model.is_wikibase() => {
+                serde_json::from_str(&self.text).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the format of the revision if any, parsed from the `format`
+    /// field.
+    pub fn content_format(&self) -> Option<ContentFormat> {
+        self.format.as_deref().map(|format| match format.parse() {
+            Ok(format) => format,
+            Err(error) => match error {},
+        })
+    }
+}
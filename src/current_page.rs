@@ -0,0 +1,117 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{
+    filter_accepts, namespace_in_range, next_page_start, page_error,
+    page_revision, parse_page_child, read_page_child, skip_element,
+    stop_after_error, Error, FromNamespaceId, PageChildElement, PageState,
+    Parser,
+};
+use std::io::BufRead;
+
+impl<R: BufRead, N: FromNamespaceId> Parser<R, N> {
+    /**
+    Reads the next page up to and including its `title` element and returns
+    the title, leaving the parser within the page.
+
+    This allows deciding from the title whether a page is needed before its
+    text is parsed. The rest of the page is then either parsed by the next
+    call to [`Iterator::next`], which returns the whole page as usual, or
+    skipped with [`Parser::skip_current_page`]. If the parser is already
+    within a page, the rest of that page is skipped first.
+
+    The children of the `page` element before the title, normally only the
+    `ns` element, are parsed as usual, so pages excluded by options such as
+    [`ParserBuilder::namespace_range`](crate::ParserBuilder::namespace_range)
+    are skipped. Returns [`Error::Format`] if a page ends without a title.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text>beta</text></revision></page>
+        <page><ns>0</ns><title>gamma</title><revision><text>delta</text></revision></page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    assert_eq!(parser.next_title().unwrap().unwrap(), "alpha");
+    parser.skip_current_page().unwrap();
+    assert_eq!(parser.next_title().unwrap().unwrap(), "gamma");
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!((&*page.title, &*page.text), ("gamma", "delta"));
+    ```
+    */
+    pub fn next_title(&mut self) -> Option<Result<String, Error>> {
+        next_title(self)
+            .map_err(|error| stop_after_error(self, error))
+            .transpose()
+    }
+
+    /**
+    Skips the rest of the page whose title was read with
+    [`Parser::next_title`], without parsing it.
+
+    Returns [`Error::NotInPage`] if the parser is not within a page, that
+    is, unless [`Parser::next_title`] has returned a title and the page has
+    not been returned or skipped since.
+    */
+    pub fn skip_current_page(&mut self) -> Result<(), Error> {
+        if self.current_page.take().is_none() {
+            return Err(Error::NotInPage(self.reader.buffer_position()));
+        }
+        skip_element(self).map_err(|error| stop_after_error(self, error))
+    }
+}
+
+fn next_title<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+) -> Result<Option<String>, Error> {
+    if parser.current_page.take().is_some() {
+        skip_element(parser)?;
+    }
+    loop {
+        if !next_page_start(parser)? {
+            return Ok(None);
+        }
+        let mut page = PageState::new();
+        match parse_until_title(parser, &mut page) {
+            Err(error) => return Err(page_error(parser, &page, error)),
+            Ok(false) => {}
+            Ok(true) => {
+                let title = page.title.clone().unwrap_or_default();
+                let namespace = page.namespace_id;
+                parser.current_page = Some(page.with_namespace(namespace));
+                return Ok(Some(title));
+            }
+        }
+    }
+}
+
+/// Parses the children of a `page` element up to and including the `title`
+/// element. Returns `false` if the page was skipped.
+fn parse_until_title<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+    page: &mut PageState<N>,
+) -> Result<bool, Error> {
+    loop {
+        match read_page_child(parser, page)? {
+            None => return Err(Error::Format(parser.reader.buffer_position())),
+            Some((PageChildElement::Revision, empty)) => {
+                if !page_revision(parser, page, empty, true)? {
+                    return Ok(false);
+                }
+            }
+            Some((element, empty)) => {
+                let title = matches!(element, PageChildElement::Title);
+                parse_page_child(parser, page, element, empty)?;
+                if !namespace_in_range(parser, page)
+                    || !filter_accepts(&parser.options, page, false)
+                {
+                    skip_element(parser)?;
+                    return Ok(false);
+                }
+                if title {
+                    return Ok(true);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{
+    source::{decode_raw, unescape_and_decode},
+    Callback, Error, ParserBuilder,
+};
+use quick_xml::events::BytesStart;
+
+/**
+Decodes values from the stream the way the parser decodes the fields of
+pages, passed to the function set with
+[`ParserBuilder::on_unknown_element`].
+
+Values are decoded with the options of the parser, such as
+[`ParserBuilder::decode_entities`], so that values extracted from elements
+the parser doesn't know are decoded the same way as the built-in fields.
+*/
+pub struct DecoderCtx<'a> {
+    options: &'a ParserBuilder,
+    position: usize,
+}
+
+impl DecoderCtx<'_> {
+    /// Unescapes and decodes the raw value of an attribute or text content
+    /// as the parser does for the fields of pages.
+    pub fn unescape(&self, bytes: &[u8]) -> Result<String, Error> {
+        unescape_and_decode(bytes, self.options)
+    }
+
+    /// Returns the current position in the stream, which is after the start
+    /// tag of the element passed along with the context.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+/// Calls the function set with [`ParserBuilder::on_unknown_element`] if any
+/// with the start tag of an element that the parser skips.
+pub(crate) fn unknown_element(
+    position: usize,
+    options: &mut ParserBuilder,
+    event: &BytesStart,
+) -> Result<(), Error> {
+    if options.on_unknown_element.is_none() {
+        return Ok(());
+    }
+    let name = decode_raw(event.name(), options)?;
+    let attributes = event
+        .attributes()
+        .filter_map(|r| r.ok())
+        .collect::<Vec<_>>();
+    let attributes = attributes
+        .iter()
+        .map(|attribute| (attribute.key, &*attribute.value))
+        .collect::<Vec<_>>();
+    // Taken out of the options while it is called so that the options can
+    // be borrowed by the context.
+    if let Some(Callback(mut callback)) = options.on_unknown_element.take() {
+        callback(
+            &name,
+            &attributes,
+            &DecoderCtx {
+                options: &*options,
+                position,
+            },
+        );
+        options.on_unknown_element = Some(Callback(callback));
+    }
+    Ok(())
+}
@@ -0,0 +1,255 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::Revision;
+
+/**
+A run of lines in a diff between the texts of two revisions, returned by
+[`Revision::diff`].
+
+Lines are split as by [`str::lines`] and counted from zero. The runs are in
+the order of the lines, so that the old text is given by the `Equal` and
+`Delete` runs and the new text by the `Equal` and `Insert` runs.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOp {
+    /// Lines removed from the old text.
+    Delete {
+        /// The index of the first line in the old text.
+        old_index: usize,
+        /// The number of lines.
+        len: usize,
+    },
+
+    /// Lines that are the same in both texts.
+    Equal {
+        /// The index of the first line in the old text.
+        old_index: usize,
+        /// The index of the first line in the new text.
+        new_index: usize,
+        /// The number of lines.
+        len: usize,
+    },
+
+    /// Lines added in the new text.
+    Insert {
+        /// The index of the first line in the new text.
+        new_index: usize,
+        /// The number of lines.
+        len: usize,
+    },
+}
+
+impl Revision {
+    /**
+    Returns a line-based diff from the text of `prev` to the text of this
+    revision. Only available with the `diff` feature.
+
+    The diff is computed with the Myers algorithm, which finds the fewest
+    inserted and deleted lines, using memory linear in the number of lines.
+    Lines common to the start or the end of both texts are matched before
+    the search, so a small edit of a long text is diffed quickly. If the
+    text of `prev` is empty, as for the first revision of a page, the diff
+    is a single `Insert` run.
+
+    ```rust
+    use parse_mediawiki_dump::DiffOp;
+
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page>
+            <ns>0</ns>
+            <title>alpha</title>
+            <revision><text>beta&#10;gamma</text></revision>
+            <revision><text>beta&#10;delta</text></revision>
+        </page>
+    </mediawiki>"#;
+    let revisions = parse_mediawiki_dump::parse_revisions_flat(dump.as_bytes())
+        .map(|item| item.map(|(_, revision)| revision))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        revisions[1].diff(&revisions[0]),
+        [
+            DiffOp::Equal { old_index: 0, new_index: 0, len: 1 },
+            DiffOp::Delete { old_index: 1, len: 1 },
+            DiffOp::Insert { new_index: 1, len: 1 },
+        ]
+    );
+    ```
+    */
+    pub fn diff(&self, prev: &Revision) -> Vec<DiffOp> {
+        let old = prev.text.lines().collect::<Vec<_>>();
+        let new = self.text.lines().collect::<Vec<_>>();
+        let mut result: Vec<DiffOp> = vec![];
+        for edit in myers(&old, &new) {
+            let merged = match (result.last_mut(), edit) {
+                (Some(DiffOp::Delete { len, .. }), Edit::Delete(_))
+                | (Some(DiffOp::Equal { len, .. }), Edit::Equal(..))
+                | (Some(DiffOp::Insert { len, .. }), Edit::Insert(_)) => {
+                    *len += 1;
+                    true
+                }
+                _ => false,
+            };
+            if !merged {
+                result.push(match edit {
+                    Edit::Delete(old_index) => {
+                        DiffOp::Delete { old_index, len: 1 }
+                    }
+                    Edit::Equal(old_index, new_index) => DiffOp::Equal {
+                        old_index,
+                        new_index,
+                        len: 1,
+                    },
+                    Edit::Insert(new_index) => {
+                        DiffOp::Insert { new_index, len: 1 }
+                    }
+                });
+            }
+        }
+        result
+    }
+}
+
+/// An edit of a single line, given by its index in the old or new text.
+#[derive(Clone, Copy)]
+enum Edit {
+    Delete(usize),
+    Equal(usize, usize),
+    Insert(usize),
+}
+
+/// Returns the shortest edit script from `old` to `new` in the order of the
+/// lines.
+fn myers(old: &[&str], new: &[&str]) -> Vec<Edit> {
+    let mut edits = vec![];
+    diff_range(old, new, 0, 0, &mut edits);
+    edits
+}
+
+/// Appends the edits from `old` to `new` to `edits`, where `old` and `new`
+/// start at the given indices in the texts.
+///
+/// This is the divide and conquer variant of the Myers algorithm, which
+/// keeps only the furthest paths on each diagonal rather than their history,
+/// so the memory used is linear in the number of lines.
+fn diff_range(
+    old: &[&str],
+    new: &[&str],
+    old_start: usize,
+    new_start: usize,
+    edits: &mut Vec<Edit>,
+) {
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(old, new)| old == new)
+        .count();
+    edits
+        .extend((0..prefix).map(|i| Edit::Equal(old_start + i, new_start + i)));
+    let (old, new) = (&old[prefix..], &new[prefix..]);
+    let (old_start, new_start) = (old_start + prefix, new_start + prefix);
+    let suffix = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let (old, new) = (&old[..old.len() - suffix], &new[..new.len() - suffix]);
+    // The search finds a split point whenever both ranges have lines left.
+    let split = if old.is_empty() || new.is_empty() {
+        None
+    } else {
+        split_point(old, new)
+    };
+    if let Some((x, y)) = split {
+        diff_range(&old[..x], &new[..y], old_start, new_start, edits);
+        diff_range(&old[x..], &new[y..], old_start + x, new_start + y, edits);
+    } else {
+        edits.extend((0..old.len()).map(|i| Edit::Delete(old_start + i)));
+        edits.extend((0..new.len()).map(|i| Edit::Insert(new_start + i)));
+    }
+    edits.extend((0..suffix).map(|i| {
+        Edit::Equal(old_start + old.len() + i, new_start + new.len() + i)
+    }));
+}
+
+/// Returns a point in the middle of a shortest edit script from `old` to
+/// `new`, found by searching from both ends at once until the paths meet.
+fn split_point(old: &[&str], new: &[&str]) -> Option<(usize, usize)> {
+    let n = old.len() as isize;
+    let m = new.len() as isize;
+    let max = (n + m + 1) / 2;
+    // The furthest x reached on each diagonal k = x - y, indexed by k + max,
+    // from the start forward and from the end backward, or -1 if the
+    // diagonal hasn't been reached.
+    let mut forward = vec![-1; 2 * max as usize + 2];
+    let mut backward = forward.clone();
+    let index = |k: isize| (k + max) as usize;
+    forward[index(1)] = 0;
+    backward[index(1)] = 0;
+    let delta = n - m;
+    // Whether the paths meet after a forward step rather than a backward
+    // step, which depends on the parity of the number of edits.
+    let odd = delta % 2 != 0;
+    // The diagonals at either end that are skipped because their paths have
+    // left the grid.
+    let (mut forward_start, mut forward_end) = (0, 0);
+    let (mut backward_start, mut backward_end) = (0, 0);
+    let furthest = |v: &[isize], k: isize, d: isize| {
+        if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+            v[index(k + 1)]
+        } else {
+            v[index(k - 1)] + 1
+        }
+    };
+    let in_range = |k: isize| k >= -max && k <= max;
+    for d in 0..max {
+        for k in (-d + forward_start..=d - forward_end).step_by(2) {
+            let mut x = furthest(&forward, k, d);
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            forward[index(k)] = x;
+            if x > n {
+                forward_end += 2;
+            } else if y > m {
+                forward_start += 2;
+            } else if odd
+                && in_range(delta - k)
+                && backward[index(delta - k)] != -1
+                && x >= n - backward[index(delta - k)]
+            {
+                return Some((x as usize, y as usize));
+            }
+        }
+        for k in (-d + backward_start..=d - backward_end).step_by(2) {
+            let mut x = furthest(&backward, k, d);
+            let mut y = x - k;
+            while x < n
+                && y < m
+                && old[(n - x - 1) as usize] == new[(m - y - 1) as usize]
+            {
+                x += 1;
+                y += 1;
+            }
+            backward[index(k)] = x;
+            if x > n {
+                backward_end += 2;
+            } else if y > m {
+                backward_start += 2;
+            } else if !odd
+                && in_range(delta - k)
+                && forward[index(delta - k)] != -1
+                && forward[index(delta - k)] >= n - x
+            {
+                let x = forward[index(delta - k)];
+                return Some((x as usize, (x - delta + k) as usize));
+            }
+        }
+    }
+    None
+}
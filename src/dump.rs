@@ -0,0 +1,75 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{FromNamespaceId, NamespaceId, Parser, ParserBuilder};
+use std::{io::BufRead, marker::PhantomData};
+
+/**
+A stream to be parsed as a dump, together with the options of the parser.
+
+Iterating over a `Dump` iterates over its pages with a [`Parser`], which
+reads naturally in a `for` loop. The namespace type is [`NamespaceId`]
+unless selected with [`Dump::namespace`].
+
+```rust
+use parse_mediawiki_dump::{Dump, ParserBuilder};
+
+let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+</mediawiki>"#;
+let mut titles = vec![];
+for page in Dump::new(dump.as_bytes()).options(ParserBuilder::new().check_xmlns(false)) {
+    titles.push(page.unwrap().title);
+}
+assert_eq!(titles, ["alpha"]);
+```
+*/
+#[derive(Debug)]
+pub struct Dump<R, N = NamespaceId> {
+    options: ParserBuilder,
+    source: R,
+    phantom: PhantomData<fn() -> N>,
+}
+
+impl<R: BufRead> Dump<R> {
+    /// Wraps a stream to be parsed with the default options.
+    pub fn new(source: R) -> Self {
+        Self {
+            options: ParserBuilder::new(),
+            source,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<R: BufRead, N: FromNamespaceId> Dump<R, N> {
+    /// Sets the options of the parser, replacing any options set before.
+    pub fn options(mut self, options: ParserBuilder) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Selects the type for the namespace of the pages.
+    pub fn namespace<M: FromNamespaceId>(self) -> Dump<R, M> {
+        Dump {
+            options: self.options,
+            source: self.source,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates the parser for the dump, the same as iterating over it.
+    pub fn parser(self) -> Parser<R, N> {
+        self.options.parse_with_namespace(self.source)
+    }
+}
+
+impl<R: BufRead, N: FromNamespaceId> IntoIterator for Dump<R, N> {
+    type Item = <Parser<R, N> as Iterator>::Item;
+    type IntoIter = Parser<R, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.parser()
+    }
+}
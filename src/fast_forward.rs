@@ -0,0 +1,288 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{
+    source::next_event, unknown_schema, Error, FromNamespaceId, NamespaceId,
+    Parser, ParserBuilder, Warning,
+};
+use quick_xml::{events::Event, Reader};
+use std::io::BufRead;
+
+impl ParserBuilder {
+    /**
+    Creates a parser that skips the `siteinfo` element by scanning the raw
+    bytes of the stream for the start tag of the first `page` element
+    instead of parsing the XML events before it. Only available with the
+    `memchr` feature.
+
+    This saves time for dumps with a large `siteinfo` element, such as one
+    with many namespaces. The root element is read and checked as by
+    [`ParserBuilder::parse`], and returns [`Error::Format`] if it isn't the
+    root element of a dump. The scan assumes that the text `<page` occurs
+    before the first page only as the start of a tag, and not within a
+    comment or a CDATA section.
+
+    The XML events between the start tag of the root element and the first
+    page are not read. Therefore the XML namespaces of elements other than
+    the root element are not checked, as with
+    [`ParserBuilder::check_xmlns`] disabled, and end tags are not required
+    to match start tags. Positions reported by the parser, in errors as well
+    as offsets, are relative to where the scan stopped, which is at or
+    after the start tag of the first page.
+    Only the first root element is read if
+    [`ParserBuilder::multiple_roots`] is enabled.
+    */
+    pub fn parse_skipping_siteinfo<R: BufRead, N: FromNamespaceId>(
+        mut self,
+        mut source: R,
+    ) -> Result<Parser<R, N>, Error> {
+        let header = read_root_start_tag(&mut source)?;
+        let mut reader = Reader::from_reader(&header[..]);
+        let mut buffer = vec![];
+        let mut namespace_buffer = vec![];
+        let (empty, namespace) = loop {
+            let (accepted, event) = next_event(
+                &mut reader,
+                &mut buffer,
+                &mut namespace_buffer,
+                &self,
+            )?;
+            let (empty, event) = match event {
+                Event::Start(event) => (false, event),
+                Event::Empty(event) => (true, event),
+                Event::Eof => {
+                    return Err(Error::Format(reader.buffer_position()))
+                }
+                _ => continue,
+            };
+            if !accepted || event.local_name() != b"mediawiki" {
+                return Err(Error::Format(reader.buffer_position()));
+            }
+            let namespace = if self.collect_warnings {
+                unknown_schema(&self, &event)?
+            } else {
+                None
+            };
+            break (empty, namespace);
+        };
+        let skipped = match empty {
+            false => skip_to_page(&mut source)?,
+            true => Skipped::End,
+        };
+        self.check_xmlns = false;
+        let mut parser = self.parse_with_namespace(source);
+        parser.reader.check_end_names(false);
+        parser.started = true;
+        parser.ended = matches!(skipped, Skipped::End);
+        parser.page_start_read = matches!(skipped, Skipped::PageStartTag);
+        if let Some(namespace) = namespace {
+            parser.warnings.push(Warning::UnknownSchema {
+                namespace,
+                position: 0,
+            });
+        }
+        Ok(parser)
+    }
+}
+
+/**
+Creates a parser that skips the `siteinfo` element by scanning the raw bytes
+of the stream for the first page, in which namespaces are represented as
+[`NamespaceId`]. See [`ParserBuilder::parse_skipping_siteinfo`].
+
+```rust
+let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <siteinfo><namespaces><namespace key="0" /></namespaces></siteinfo>
+    <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+</mediawiki>"#;
+let titles = parse_mediawiki_dump::parse_skipping_siteinfo(dump.as_bytes())
+    .unwrap()
+    .map(|page| page.map(|page| page.title))
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+assert_eq!(titles, ["alpha"]);
+```
+*/
+pub fn parse_skipping_siteinfo<R: BufRead>(
+    source: R,
+) -> Result<Parser<R, NamespaceId>, Error> {
+    ParserBuilder::new().parse_skipping_siteinfo(source)
+}
+
+/// Reads the stream up to and including the start tag of the root element,
+/// returning the bytes read. Each markup before it must end with `>`, as the
+/// XML declaration, comments and the document type declaration do.
+fn read_root_start_tag(source: &mut impl BufRead) -> Result<Vec<u8>, Error> {
+    let mut header = vec![];
+    loop {
+        let buffer = source.fill_buf().map_err(|source| Error::Io {
+            source,
+            position: header.len(),
+        })?;
+        if buffer.is_empty() {
+            return Err(Error::UnexpectedEof(header.len()));
+        }
+        let end = memchr::memchr(b'>', buffer).map(|index| index + 1);
+        let length = end.unwrap_or(buffer.len());
+        header.extend_from_slice(&buffer[..length]);
+        source.consume(length);
+        if end.is_some() && is_root_start_tag(&header) {
+            return Ok(header);
+        }
+    }
+}
+
+/// Returns whether the markup at the end of `header` is a start tag named
+/// `mediawiki`, with any namespace prefix.
+fn is_root_start_tag(header: &[u8]) -> bool {
+    let start = match memchr::memrchr(b'<', header) {
+        None => return false,
+        Some(start) => start + 1,
+    };
+    let tag = &header[start..];
+    let name = tag
+        .split(|byte| byte.is_ascii_whitespace() || b"/>".contains(byte))
+        .next()
+        .unwrap_or_default();
+    let local_name = name.rsplit(|byte| *byte == b':').next();
+    local_name == Some(b"mediawiki")
+}
+
+/// Where [`skip_to_page`] stopped.
+enum Skipped {
+    /// At the end of the stream, after the end tag of the root element.
+    End,
+    /// At the start tag of the first page.
+    PageStart,
+    /// After the start tag of the first page.
+    PageStartTag,
+}
+
+/// Consumes the stream up to the start tag of the first `page` element.
+///
+/// If the start tag begins at the end of a buffer of the stream, the start
+/// of it is consumed to get to the next buffer, and then the rest of it is
+/// consumed as well. If there is no page, the stream is consumed to the end
+/// and must end with the end tag of the root element.
+fn skip_to_page(source: &mut impl BufRead) -> Result<Skipped, Error> {
+    const TAG: &[u8] = b"<page";
+    let io_error = |source| Error::Io {
+        source,
+        position: 0,
+    };
+    // The consumed bytes at the end of the previous buffer that may be the
+    // start of the start tag.
+    let mut pending = vec![];
+    // The last consumed bytes, to check the end of the stream.
+    let mut tail = vec![];
+    loop {
+        let buffer = source.fill_buf().map_err(io_error)?;
+        if buffer.is_empty() {
+            return match is_root_end_tag(&tail) {
+                false => Err(Error::UnexpectedEof(0)),
+                true => Ok(Skipped::End),
+            };
+        }
+        if !pending.is_empty() {
+            let length = (TAG.len() + 1 - pending.len()).min(buffer.len());
+            pending.extend_from_slice(&buffer[..length]);
+            if is_page_start(&pending) {
+                source.consume(length);
+                if pending.len() > TAG.len() {
+                    if pending[TAG.len()] != b'>' {
+                        skip_page_start_tag(source)?;
+                    }
+                    return Ok(Skipped::PageStartTag);
+                }
+                continue;
+            }
+            pending.clear();
+        }
+        if let Some(index) =
+            memchr::memmem::find_iter(buffer, TAG).find(|&index| {
+                buffer.len() > index + TAG.len()
+                    && is_page_start(&buffer[index..=index + TAG.len()])
+            })
+        {
+            source.consume(index);
+            return Ok(Skipped::PageStart);
+        }
+        if let Some(index) = memchr::memrchr(b'<', buffer) {
+            if is_page_start(&buffer[index..]) {
+                pending.extend_from_slice(&buffer[index..]);
+            }
+        }
+        tail.extend_from_slice(&buffer[buffer.len().saturating_sub(TAIL)..]);
+        tail.drain(..tail.len().saturating_sub(TAIL));
+        let length = buffer.len();
+        source.consume(length);
+    }
+}
+
+/// The number of bytes at the end of the stream kept to check that it ends
+/// with the end tag of the root element.
+const TAIL: usize = 64;
+
+/// Returns whether `bytes` ends with an end tag named `mediawiki`, with any
+/// namespace prefix, followed by nothing but whitespace.
+fn is_root_end_tag(bytes: &[u8]) -> bool {
+    let end = match bytes.iter().rposition(|byte| !byte.is_ascii_whitespace()) {
+        Some(end) if bytes[end] == b'>' => end,
+        _ => return false,
+    };
+    let start = match memchr::memmem::rfind(&bytes[..end], b"</") {
+        None => return false,
+        Some(start) => start + 2,
+    };
+    let name = bytes[start..end]
+        .split(|byte| byte.is_ascii_whitespace())
+        .next()
+        .unwrap_or_default();
+    name.rsplit(|byte| *byte == b':').next() == Some(b"mediawiki")
+}
+
+/// Returns whether `bytes` is the start of the start tag of a `page`
+/// element, not longer than the name and the byte after it.
+fn is_page_start(bytes: &[u8]) -> bool {
+    match bytes.split_at(bytes.len().min(5)) {
+        (name, []) => b"<page".starts_with(name),
+        (name, [next]) => {
+            name == b"<page" && (*next == b'>' || next.is_ascii_whitespace())
+        }
+        _ => false,
+    }
+}
+
+/// Consumes the rest of a start tag of a `page` element up to and including
+/// its `>`.
+fn skip_page_start_tag(source: &mut impl BufRead) -> Result<(), Error> {
+    let mut previous = 0;
+    loop {
+        let buffer = source.fill_buf().map_err(|source| Error::Io {
+            source,
+            position: 0,
+        })?;
+        if buffer.is_empty() {
+            return Err(Error::UnexpectedEof(0));
+        }
+        match memchr::memchr(b'>', buffer) {
+            None => {
+                previous = buffer[buffer.len() - 1];
+                let length = buffer.len();
+                source.consume(length);
+            }
+            Some(index) => {
+                if index > 0 {
+                    previous = buffer[index - 1];
+                }
+                source.consume(index + 1);
+                // A `page` element must not be empty.
+                return match previous {
+                    b'/' => Err(Error::Format(0)),
+                    _ => Ok(()),
+                };
+            }
+        }
+    }
+}
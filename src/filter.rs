@@ -0,0 +1,108 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{NamespaceId, PageState, ParserBuilder};
+
+/**
+Predicates on pages combined into one filter, set with
+[`ParserBuilder::filter`](crate::ParserBuilder::filter).
+
+A page is returned only if it passes every predicate that is set. The
+predicates are checked from the cheapest to the most expensive: namespace,
+redirect, title and model. They are checked after each child element of the
+page, so that the rest of a page that is known not to pass is skipped as
+soon as possible, before the `text` element, which is skipped without being
+decoded if the model doesn't pass.
+
+```rust
+let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+    <page><ns>1</ns><title>Talk:alpha</title><revision><text/></revision></page>
+    <page>
+        <ns>0</ns>
+        <title>beta</title>
+        <redirect title="alpha" />
+        <revision><text>#REDIRECT [[alpha]]</text></revision>
+    </page>
+</mediawiki>"#;
+let titles = parse_mediawiki_dump::ParserBuilder::new()
+    .filter(parse_mediawiki_dump::Filter {
+        namespaces: Some(vec![parse_mediawiki_dump::NamespaceId(0)]),
+        exclude_redirects: true,
+        ..Default::default()
+    })
+    .parse(dump.as_bytes())
+    .map(|page| page.map(|page| page.title))
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+assert_eq!(titles, ["alpha"]);
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+    /// Whether to skip pages that are redirects.
+    pub exclude_redirects: bool,
+
+    /// The models that pages must have, if any. A page without a `model`
+    /// element is skipped if this is set. Compared with the model as given
+    /// in the `model` field of the page, normalized if enabled with
+    /// [`ParserBuilder::normalize_model`](crate::ParserBuilder::normalize_model).
+    pub models: Option<Vec<String>>,
+
+    /// The namespaces that pages must be in, if any.
+    pub namespaces: Option<Vec<NamespaceId>>,
+
+    /// A regular expression that the titles of pages must match, if any.
+    /// Only available with the `regex` feature.
+    #[cfg(feature = "regex")]
+    pub title_regex: Option<regex::Regex>,
+}
+
+impl Filter {
+    /// Returns whether a page passes the filter. Unless the page is
+    /// `complete`, predicates on fields not parsed yet are passed.
+    pub(crate) fn accepts<N>(
+        &self,
+        page: &PageState<N>,
+        complete: bool,
+    ) -> bool {
+        if let (Some(namespaces), Some(id)) =
+            (&self.namespaces, page.namespace_id)
+        {
+            if !namespaces.contains(&id) {
+                return false;
+            }
+        }
+        if self.exclude_redirects && page.redirect_title.is_some() {
+            return false;
+        }
+        #[cfg(feature = "regex")]
+        {
+            if let (Some(regex), Some(title)) = (&self.title_regex, &page.title)
+            {
+                if !regex.is_match(title) {
+                    return false;
+                }
+            }
+        }
+        match (&self.models, &page.model) {
+            (Some(models), Some(model)) => models.contains(model),
+            (Some(_), None) => !complete,
+            (None, _) => true,
+        }
+    }
+}
+
+/// Returns whether a page passes the filter set with
+/// [`ParserBuilder::filter`], if any.
+pub(crate) fn filter_accepts<N>(
+    options: &ParserBuilder,
+    page: &PageState<N>,
+    complete: bool,
+) -> bool {
+    match &options.filter {
+        None => true,
+        Some(filter) => filter.accepts(page, complete),
+    }
+}
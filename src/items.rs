@@ -0,0 +1,107 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{Error, FromNamespaceId, NamespaceId, Page, Parser};
+use std::{io::BufRead, iter::FusedIterator};
+
+/// Item returned by [`Items`].
+// All items but the last are pages, so boxing the page would only add
+// an allocation for each page.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum Item<N> {
+    /// A parsed page.
+    Page(Page<N>),
+
+    /// The end of the stream, always the last item.
+    End {
+        /// Whether the stream ended cleanly, with the end tag of the root
+        /// element followed only by whitespace, comments and processing
+        /// instructions.
+        clean: bool,
+    },
+}
+
+/// Iterator over pages followed by a marker telling whether the stream
+/// ended cleanly.
+///
+/// Created by [`Parser::items`] and [`parse_items`].
+pub struct Items<R: BufRead, N> {
+    /// Whether the end has been returned.
+    done: bool,
+    parser: Parser<R, N>,
+    /// Whether the stream was found to end before the end of the root
+    /// element.
+    truncated: bool,
+}
+
+impl<R: BufRead, N: FromNamespaceId> Parser<R, N> {
+    /**
+    Converts the parser into an iterator over pages that returns
+    [`Item::End`] as its last item, telling whether the stream ended
+    cleanly.
+
+    A parser returns `None` both after the end of the dump and after an error
+    it can't recover from, so this is useful for recording that a dump was
+    imported completely. Errors are returned as they occur. After the last
+    page, the rest of the stream is read as with [`Parser::drain_to_end`].
+
+    ```rust
+    use parse_mediawiki_dump::Item;
+
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let mut items = parse_mediawiki_dump::parse_items(dump.as_bytes());
+    assert!(matches!(items.next(), Some(Ok(Item::Page(_)))));
+    assert!(matches!(items.next(), Some(Ok(Item::End { clean: true }))));
+    assert!(items.next().is_none());
+    ```
+    */
+    pub fn items(self) -> Items<R, N> {
+        Items {
+            done: false,
+            parser: self,
+            truncated: false,
+        }
+    }
+}
+
+/// Creates an iterator over the pages of a stream followed by a marker
+/// telling whether the stream ended cleanly. See [`Parser::items`].
+pub fn parse_items<R: BufRead>(source: R) -> Items<R, NamespaceId> {
+    crate::parse(source).items()
+}
+
+impl<R: BufRead, N: FromNamespaceId> Iterator for Items<R, N> {
+    type Item = Result<Item<N>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        Some(match self.parser.next() {
+            Some(Ok(page)) => Ok(Item::Page(page)),
+            Some(Err(error)) => {
+                let inner = match &error {
+                    Error::Page { error, .. } => error,
+                    error => error,
+                };
+                if let Error::UnexpectedEof(_) = inner {
+                    self.truncated = true;
+                }
+                Err(error)
+            }
+            None => {
+                self.done = true;
+                let clean = self.parser.drain_to_end().is_ok();
+                Ok(Item::End {
+                    clean: clean && !self.truncated,
+                })
+            }
+        })
+    }
+}
+
+impl<R: BufRead, N: FromNamespaceId> FusedIterator for Items<R, N> {}
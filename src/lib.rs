@@ -26,10 +26,15 @@ This module only parses dumps containing only one revision of each page.
 This is what you get from the page `Special:Export` when enabling the option
 “Include only the current revision, not the full history”, as well as what you
 get from the Wikimedia dumps with file names ending with `-pages-articles.xml.bz2`.
+Dumps with the full history of each page can be parsed one revision at a time
+with [`parse_revisions_flat`].
 
-This module ignores the `siteinfo` element, every child element of the `page`
-element except `ns`, `revision` and `title`, and every element inside the
-`revision` element except `format`, `model` and `text`.
+This module ignores the `siteinfo` element unless enabled with
+[`ParserBuilder::capture_site_info`], every child element of the `page`
+element except `id`, `ns`, `redirect`, `revision` and `title`, and every
+element inside the `revision` element except `format`, `minor`, `model`,
+`sha1`, `text` and `timestamp`, and `comment` and `contributor`, of which
+only the `deleted` attribute is read.
 
 Until there is a real use case that justifies going beyond these limitations,
 they will remain in order to avoid premature design driven by imagined requirements.
@@ -41,32 +46,24 @@ A running example with complete error handling is available in the
 `examples` folder.
 
 ```rust,no_run
-fn main() {
-    let file = std::fs::File::open("example.xml.bz2").unwrap();
-    let file = std::io::BufReader::new(file);
-    let file = bzip2::bufread::BzDecoder::new(file);
-    let file = std::io::BufReader::new(file);
-    for result in parse_mediawiki_dump::parse(file) {
-        match result {
-            Err(error) => {
-                eprintln!("Error: {}", error);
-                break;
-            }
-            Ok(page) => if page.namespace.into_inner() == 0 && match &page.format {
-                None => false,
-                Some(format) => format == "text/x-wiki"
-            } && match &page.model {
-                None => false,
-                Some(model) => model == "wikitext"
-            } {
-                println!(
-                    "The page {title:?} is an ordinary article with byte length {length}.",
-                    title = page.title,
-                    length = page.text.len()
-                );
-            } else {
-                println!("The page {:?} has something special to it.", page.title);
-            }
+let file = std::fs::File::open("example.xml.bz2").unwrap();
+let file = std::io::BufReader::new(file);
+let file = bzip2::bufread::BzDecoder::new(file);
+let file = std::io::BufReader::new(file);
+for result in parse_mediawiki_dump::parse(file) {
+    match result {
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            break;
+        }
+        Ok(page) => if page.is_content_article() {
+            println!(
+                "The page {title:?} is an ordinary article with byte length {length}.",
+                title = page.title,
+                length = page.text.len()
+            );
+        } else {
+            println!("The page {:?} has something special to it.", page.title);
         }
     }
 }
@@ -76,11 +73,100 @@ fn main() {
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
-use quick_xml::{events::Event, Reader};
+#[cfg(feature = "bumpalo")]
+mod arena;
+#[cfg(feature = "arrow")]
+mod arrow;
+mod chained;
+#[cfg(feature = "tokio")]
+mod channel;
+mod chunks;
+mod content;
+mod current_page;
+mod decoder;
+#[cfg(feature = "diff")]
+mod diff;
+mod dump;
+#[cfg(feature = "memchr")]
+mod fast_forward;
+mod filter;
+mod items;
+mod maybe_namespace;
+mod namespace_fn;
+mod offsets;
+mod order;
+mod parallel;
+mod redirects;
+mod resume;
+mod revisions;
+mod search;
+mod site_info;
+mod sort;
+mod source;
+mod stats;
+mod text_reader;
+mod thread;
+mod title;
+mod validate;
+mod warning;
+
+#[cfg(feature = "bumpalo")]
+pub use arena::ArenaPage;
+#[cfg(feature = "arrow")]
+pub use arrow::{parse_record_batches, RecordBatches, TextColumn};
+pub use chained::{parse_chained, ChainedReader};
+#[cfg(feature = "tokio")]
+pub use channel::parse_into_channel;
+pub use chunks::Chunks;
+pub use content::{ContentFormat, ContentModel};
+use decoder::unknown_element;
+pub use decoder::DecoderCtx;
+#[cfg(feature = "diff")]
+pub use diff::DiffOp;
+pub use dump::Dump;
+#[cfg(feature = "memchr")]
+pub use fast_forward::parse_skipping_siteinfo;
+use filter::filter_accepts;
+pub use filter::Filter;
+pub use items::{parse_items, Item, Items};
+pub use maybe_namespace::MaybeNamespace;
+pub use namespace_fn::{parse_with_namespace_fn, NamespaceFnParser};
+pub use offsets::EnumerateOffsets;
+pub use order::DumpOrd;
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+pub use redirects::{follow_redirect, resolve_redirects, MAX_REDIRECT_DEPTH};
+pub use resume::parse_from_offset;
+pub use revisions::{
+    parse_revisions_flat, ByTimestamp, Revision, RevisionsFlat,
+};
+use search::StreamSearch;
+use site_info::parse_site_info;
+pub use site_info::{DynamicNamespaces, SiteInfo};
+use source::{decode_raw, next_event, unescape_and_decode};
+pub use stats::ParserStats;
 use std::{
-    convert::TryInto, hash::Hash, io::BufRead, marker::PhantomData,
-    str::FromStr, borrow::Cow,
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+    hash::{BuildHasher, Hash},
+    io::BufRead,
+    iter::FusedIterator,
+    marker::PhantomData,
+    ops::{ControlFlow, Range, RangeInclusive},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
 };
+pub use text_reader::TextReader;
+pub use thread::Thread;
+pub use title::{normalize_title, title_key, Case};
+pub use validate::{validate, ValidationReport};
+use warning::warn;
+pub use warning::Warning;
 
 /**
 The default namespace type in the [`Page`] struct.
@@ -202,7 +288,31 @@ where
     }
 }
 
+/// A field of a page passed to the function set with
+/// [`ParserBuilder::text_transform`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Field {
+    /// The `format` field, from the `format` element.
+    Format,
+    /// The `model` field, from the `model` element.
+    Model,
+    /// The `redirect_title` field, from the `title` attribute of the
+    /// `redirect` element.
+    RedirectTitle,
+    /// The `text` field, from the `text` element.
+    Text,
+    /// The `title` field, from the `title` element.
+    Title,
+}
+
 enum PageChildElement {
+    /// A `DiscussionThreading` element, parsed if enabled with
+    /// [`ParserBuilder::capture_threads`].
+    DiscussionThreading,
+    /// An element captured with the given name if enabled with
+    /// [`ParserBuilder::capture_unknown_page_fields`].
+    Extra(String),
+    Id,
     Ns,
     Revision,
     Title,
@@ -211,31 +321,166 @@ enum PageChildElement {
 }
 
 enum RevisionChildElement {
+    Comment,
+    Contributor,
     Format,
+    Minor,
     Model,
+    Sha1,
     Text,
+    Timestamp,
     Unknown,
 }
 
-#[derive(Debug)]
+#[derive(Debug, thiserror::Error)]
 /// The error type for `Parser`.
 pub enum Error {
     /// Format not matching expectations.
     ///
     /// Indicates the position in the stream.
+    #[error("Invalid format at position {0}")]
     Format(usize),
 
     /// The source contains a feature not supported by the parser.
     ///
     /// In particular, this means a `page` element contains more than one `revision` element.
+    #[error("The element at position {0} is not supported")]
     NotSupported(usize),
 
     /// Error from the XML reader.
-    XmlReader(quick_xml::Error),
+    ///
+    /// Errors from reading the underlying stream are instead returned as
+    /// [`Error::Io`].
+    #[error("{0}")]
+    XmlReader(#[from] quick_xml::Error),
+
+    /// Error from reading the underlying stream, for example from
+    /// a decompressor when the stream is truncated or corrupt.
+    #[error("Failed to read the stream after position {position}: {source}")]
+    Io {
+        /// The error.
+        source: std::io::Error,
+        /// The position in the stream up to which it was parsed.
+        position: usize,
+    },
 
     /// Namespace id could not be converted to selected namespace type.
     #[allow(missing_docs)]
+    #[error(
+        "The namespace {} at position {position} was not recognized",
+        .id.into_inner()
+    )]
     Namespace { id: NamespaceId, position: usize },
+
+    /// Namespace id of a page already parsed could not be converted to
+    /// the selected namespace type by [`Page::try_into_namespace`].
+    #[error("The namespace {} was not recognized", .0.into_inner())]
+    UnknownNamespace(NamespaceId),
+
+    /// The text of an `ns` element is not a valid namespace id, for example
+    /// because it is not a number or does not fit in an `i32`.
+    #[error("The namespace id {value:?} at position {position} is invalid")]
+    InvalidNamespaceId {
+        /// The text of the `ns` element.
+        value: String,
+        /// The position in the stream.
+        position: usize,
+    },
+
+    /// The stream ended before the end tag of the root element.
+    ///
+    /// Indicates the position in the stream, which is the length of the
+    /// stream. Usually means the stream was truncated.
+    #[error("Unexpected end of stream at position {0}")]
+    UnexpectedEof(usize),
+
+    /// [`Parser::skip_current_page`] or
+    /// [`RevisionsFlat::skip_current_page`] was called when the parser was
+    /// not within a page.
+    ///
+    /// Indicates the position in the stream.
+    #[error("Not within a page at position {0}")]
+    NotInPage(usize),
+
+    /// Parsing was cancelled with the flag set with
+    /// [`ParserBuilder::cancellation`].
+    #[error("Parsing was cancelled at position {position}")]
+    Cancelled {
+        /// The position in the stream where parsing stopped.
+        position: usize,
+    },
+
+    /// A page has more revisions than the limit set with
+    /// [`ParserBuilder::max_revisions_per_page`].
+    #[error(
+        "The revision at position {position} exceeds the limit of {limit} \
+         revisions per page"
+    )]
+    TooManyRevisions {
+        /// The limit.
+        limit: usize,
+        /// The position in the stream of the first revision exceeding
+        /// the limit.
+        position: usize,
+    },
+
+    /// A revision has a content model not allowed with
+    /// [`ParserBuilder::allowed_models`].
+    #[error(
+        "The content model {model:?} at position {position} is not allowed"
+    )]
+    UnexpectedModel {
+        /// The content model.
+        model: String,
+        /// The position in the stream after the `model` element.
+        position: usize,
+    },
+
+    /// A revision has a format not allowed with
+    /// [`ParserBuilder::allowed_formats`].
+    #[error("The format {format:?} at position {position} is not allowed")]
+    UnexpectedFormat {
+        /// The format.
+        format: String,
+        /// The position in the stream after the `format` element.
+        position: usize,
+    },
+
+    /// A page has the same namespace and title as a page before it, as
+    /// detected with [`ParserBuilder::detect_duplicate_titles`].
+    #[error("The title {title:?} at position {position} is a duplicate")]
+    DuplicateTitle {
+        /// The title of the page.
+        title: String,
+        /// The position in the stream after the page.
+        position: usize,
+    },
+
+    /// An error within a `page` element, together with the fields of the page
+    /// that were parsed before the error.
+    ///
+    /// Only returned if enabled with [`ParserBuilder::partial_page_errors`].
+    /// The wrapped error is never itself `Error::Page`.
+    #[error("{error}{}", InPage(.page))]
+    Page {
+        /// The fields of the page parsed before the error.
+        page: PartialPage,
+        /// The error.
+        #[source]
+        error: Box<Error>,
+    },
+}
+
+/// The fields of a page parsed before an error or a warning occurred.
+///
+/// Returned in [`Error::Page`] and [`Warning::SkippedUnsupported`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialPage {
+    /// The namespace id of the page, if the `ns` element was parsed.
+    pub namespace: Option<NamespaceId>,
+
+    /// The title of the page, if the `title` element was parsed.
+    pub title: Option<String>,
 }
 
 /**
@@ -251,6 +496,11 @@ Although the `format` and `model` elements are defined as mandatory in the
 [schema], previous versions of the schema don't contain them.
 Therefore the corresponding fields can be `None`.
 
+The child elements of the `page` and `revision` elements are accepted in any
+order, although the schema defines a sequence for them, so for example
+a `title` element after the `ns` element is parsed the same way as one before
+it.
+
 The implementations of [`PartialOrd`], [`Ord`], [`PartialEq`], [`Eq`],
 and [`Hash`] for this type only look at the `namespace` and `title` fields,
 as the `page` table is set up so that this pair of fields is unique
@@ -260,7 +510,7 @@ for every page (see the [database installation script]).
 [database installation script]:
 https://phabricator.wikimedia.org/source/mediawiki/browse/master/maintenance/tables.sql;aa3c07964c56$279
 */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Page<N> {
     /// The title of the page with the namespace prefix.
     ///
@@ -272,15 +522,22 @@ pub struct Page<N> {
     of the page.
 
     Parsed from the text content of the `ns` element in the `page` element.
+    Whitespace around the id is ignored.
 
     All parsing functions require that this field implement `FromNamespaceId`.
     */
     pub namespace: N,
 
+    /// The id of the page if any.
+    ///
+    /// Parsed from the text content of the `id` element in the `page` element.
+    pub page_id: Option<u64>,
+
     /// The format of the revision if any.
     ///
     /// Parsed from the text content of the `format` element in the `revision`
-    /// element. `None` if the element is not present.
+    /// element. `None` if the element is not present, is empty, as in
+    /// `<format/>`, or contains only whitespace.
     ///
     /// For ordinary articles the format is `text/x-wiki`.
     pub format: Option<String>,
@@ -288,11 +545,30 @@ pub struct Page<N> {
     /// The model of the revision if any.
     ///
     /// Parsed from the text content of the `model` element in the `revision`
-    /// element. `None` if the element is not present.
+    /// element. `None` if the element is not present, is empty, as in
+    /// `<model/>`, or contains only whitespace.
     ///
     /// For ordinary articles the model is `wikitext`.
     pub model: Option<String>,
 
+    /// The format of the revision as written in the dump, if any.
+    ///
+    /// Only present if enabled with [`ParserBuilder::normalize_model`],
+    /// in which case the `format` field is normalized.
+    pub raw_format: Option<String>,
+
+    /// The model of the revision as written in the dump, if any.
+    ///
+    /// Only present if enabled with [`ParserBuilder::normalize_model`],
+    /// in which case the `model` field is normalized.
+    pub raw_model: Option<String>,
+
+    /// The text content of the `ns` element as written in the dump,
+    /// including any whitespace or leading zeros.
+    ///
+    /// Only present if enabled with [`ParserBuilder::raw_namespace`].
+    pub raw_namespace: Option<String>,
+
     /// The text of the revision.
     ///
     /// Parsed from the text content of the `text` element in the `revision` element.
@@ -305,15 +581,577 @@ pub struct Page<N> {
     ///
     /// For pages that are not redirects, the `redirect` element is not present.
     pub redirect_title: Option<String>,
+
+    /// The SHA-1 of the text of the revision if any.
+    ///
+    /// Parsed from the text content of the `sha1` element in the `revision`
+    /// element. MediaWiki stores the SHA-1 as a base 36 number with
+    /// lowercase letters, padded with zeros to 31 digits.
+    pub sha1: Option<String>,
+
+    /// The SHA-1 of the text of the revision, computed while parsing.
+    ///
+    /// Only available with the `sha1` feature and only present if enabled
+    /// with [`ParserBuilder::text_sha1`]. Formatted the same way as the
+    /// `sha1` field, so the two can be compared to verify the text.
+    #[cfg(feature = "sha1")]
+    pub text_sha1: Option<String>,
+
+    /// Which fields of the revision have been deleted.
+    ///
+    /// Parsed from the `deleted` attribute of the `comment`, `contributor` and
+    /// `text` elements in the `revision` element.
+    pub deleted: Deleted,
+
+    /// The byte offsets of the start and end of the content of the `text`
+    /// element in the stream.
+    ///
+    /// Only present if enabled with [`ParserBuilder::text_spans`],
+    /// in which case the `text` field is empty.
+    pub text_span: Option<(usize, usize)>,
+
+    /// Whether the `text` element in the `revision` element is written in
+    /// the empty-element form `<text/>` rather than as `<text></text>`.
+    ///
+    /// Only recorded if enabled with
+    /// [`ParserBuilder::record_empty_text_element`], otherwise `false`.
+    pub text_was_empty_element: bool,
+
+    /// The location of the text of the revision in an external store if any.
+    ///
+    /// Parsed from the `location` attribute of the `text` element in the
+    /// `revision` element. Some dumps leave the `text` element empty and
+    /// give the location of the text instead, in which case the `text`
+    /// field is empty unless the text is fetched with
+    /// [`ParserBuilder::resolve_text`].
+    pub text_location: Option<String>,
+
+    /// The length of the text of the revision in bytes, as declared in
+    /// the dump, if any.
+    ///
+    /// Parsed from the `bytes` attribute of the `text` element in the
+    /// `revision` element, which can be compared with the length of the
+    /// `text` field to check the integrity of the dump.
+    pub declared_bytes: Option<u64>,
+
+    /// The SHA-1 of the text of the revision as declared in the `sha1`
+    /// attribute of the `text` element in the `revision` element, if any.
+    ///
+    /// Formatted the same way as the `sha1` field.
+    pub declared_sha1: Option<String>,
+
+    /// Attributes of the `redirect` element other than `title`, as
+    /// name-value pairs in document order.
+    ///
+    /// No such attributes are defined in the
+    /// [schema](https://www.mediawiki.org/xml/export-0.10.xsd), but they
+    /// don't cause an error and are kept here in case an export contains
+    /// them.
+    /// Empty for pages that are not redirects.
+    pub redirect_attributes: Vec<(String, String)>,
+
+    /// The text content of the child elements of the `page` element that
+    /// aren't otherwise parsed, by the local name of the element.
+    ///
+    /// Only filled if enabled with
+    /// [`ParserBuilder::capture_unknown_page_fields`].
+    pub extra: HashMap<String, String>,
+
+    /// The threading metadata of the page if it is a thread of the
+    /// LiquidThreads extension.
+    ///
+    /// Only present if enabled with [`ParserBuilder::capture_threads`].
+    pub thread: Option<Thread>,
+
+    /// A hash of the namespace and the title of the page, as computed by
+    /// [`title_key`].
+    ///
+    /// Only present if enabled with [`ParserBuilder::title_key_hasher`].
+    pub title_key: Option<u64>,
+}
+
+/**
+Metadata of a parsed page.
+
+Parsed from the `page` element by [`parse_meta`], which skips the `revision`
+element entirely, so the text, format and model of the revision are neither
+read nor allocated. This makes it considerably cheaper than [`parse`] when
+only titles, namespaces and redirects are needed, for example to build
+a table of redirects. On a dump of pages with about 5 kB of text each,
+parsing the metadata takes about a quarter of the time of parsing
+the full pages.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageMeta<N> {
+    /// The id of the page if any.
+    ///
+    /// Parsed from the text content of the `id` element in the `page` element.
+    pub page_id: Option<u64>,
+
+    /// The namespace of the page.
+    ///
+    /// Parsed from the text content of the `ns` element in the `page` element.
+    pub namespace: N,
+
+    /// The redirect target if any.
+    ///
+    /// Parsed from the content of the `title` attribute of the `redirect`
+    /// element in the `page` element.
+    pub redirect_title: Option<String>,
+
+    /// The title of the page with the namespace prefix.
+    ///
+    /// Parsed from the text content of the `title` element in the `page` element.
+    pub title: String,
+}
+
+/**
+Fields of a revision that have been deleted (suppressed).
+
+When a field of a revision is hidden with [revision deletion], the dump
+contains an empty element with the attribute `deleted="deleted"` in place
+of its content, for example `<text deleted="deleted" />`. For a deleted text,
+the `text` field of the page is empty.
+
+[revision deletion]: https://www.mediawiki.org/wiki/Help:RevisionDelete
+*/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Deleted {
+    /// Whether the edit summary has been deleted.
+    pub comment: bool,
+
+    /// Whether the user name or IP address of the contributor
+    /// has been deleted.
+    pub contributor: bool,
+
+    /// Whether the text has been deleted.
+    pub text: bool,
+}
+
+/**
+Parser working as an iterator over pages.
+
+Adapters such as [`Iterator::take`] consume the parser. To keep parsing
+afterwards, use them on [`Iterator::by_ref`], as `&mut Parser` is also
+an iterator.
+
+```rust
+let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+    <page><ns>0</ns><title>beta</title><revision><text/></revision></page>
+</mediawiki>"#;
+let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+for page in parser.by_ref().take(1) {
+    assert_eq!(page.unwrap().title, "alpha");
 }
+assert_eq!(parser.next().unwrap().unwrap().title, "beta");
+```
+
+After returning an error, the parser returns `None`, except after
+[`Error::TooManyRevisions`], because the position
+in the stream after an error is usually in the middle of a page, and reading
+on would only give more errors that are consequences of the first one.
+The parser is therefore a [`FusedIterator`]. To keep parsing after errors
+anyway, enable [`ParserBuilder::recover_from_errors`].
+
+```rust
+let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page><ns>zero</ns><title>alpha</title><revision><text/></revision></page>
+    <page><ns>0</ns><title>beta</title><revision><text/></revision></page>
+</mediawiki>"#;
+let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+assert!(parser.next().unwrap().is_err());
+assert!(parser.next().is_none());
+```
+
+The parser is [`Send`] if the reader is, whatever the type of the namespace,
+so it can be moved to another thread. For this reason the functions given to
+[`ParserBuilder`] must be `Send` too. The parser is not [`Sync`], which would
+be of no use since parsing requires a mutable reference.
 
-/// Parser working as an iterator over pages.
+```rust
+let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+</mediawiki>"#;
+let parser = parse_mediawiki_dump::parse(dump.as_bytes());
+let titles = std::thread::spawn(move || {
+    parser
+        .map(|page| page.map(|page| page.title))
+        .collect::<Result<Vec<_>, _>>()
+})
+.join()
+.unwrap()
+.unwrap();
+assert_eq!(titles, ["alpha"]);
+```
+*/
 pub struct Parser<R: BufRead, Namespace> {
     buffer: Vec<u8>,
+    /// The fields of the page read by [`Parser::next_title`], whose start
+    /// tag has been read but not its end tag. The namespace is kept as an id
+    /// so that the parser is `Send` whatever the namespace type.
+    current_page: Option<PageState<NamespaceId>>,
+    /// Whether the end of the dump has been reached.
+    ended: bool,
+    /// The namespace of the last page returned, if any.
+    last_namespace: Option<NamespaceId>,
     namespace_buffer: Vec<u8>,
+    options: ParserBuilder,
+    /// The number of `page` elements started, for
+    /// [`ParserBuilder::page_range`].
+    page_index: usize,
+    page_start: usize,
+    /// Whether the start tag of the next page has already been consumed
+    /// from the stream.
+    page_start_read: bool,
+    past_range: bool,
     reader: Reader<R>,
+    /// The namespaces and titles of the pages returned, for
+    /// [`ParserBuilder::detect_duplicate_titles`].
+    seen_titles: HashSet<(i32, String)>,
+    /// Whether the parser is skipping to the next page after an error,
+    /// possibly from within a page.
+    recovering: bool,
+    shared_position: Option<Arc<AtomicUsize>>,
+    site_info: Option<SiteInfo>,
     started: bool,
-    phantom: PhantomData<Namespace>,
+    stats: ParserStats,
+    /// The buffer given to [`Parser::next_text_into`] until the text of
+    /// a page is parsed into it, otherwise empty.
+    text_buffer: String,
+    /// The length of the content of the buffer given to
+    /// [`Parser::next_text_into`] while it parses a page.
+    text_buffer_length: Option<usize>,
+    warnings: Vec<Warning>,
+    phantom: PhantomData<fn() -> Namespace>,
+}
+
+/**
+Builder for a [`Parser`] with options that differ from the defaults.
+
+The functions [`parse`] and [`parse_with_namespace`] are equivalent to
+the methods of the same names on `ParserBuilder::new()`.
+
+```rust
+let parser = parse_mediawiki_dump::ParserBuilder::new()
+    .accept_schema_versions(&[(0, 10), (0, 11)])
+    .parse(&b"<mediawiki xmlns=\"http://www.mediawiki.org/xml/export-0.10/\"/>"[..]);
+```
+*/
+#[derive(Debug)]
+pub struct ParserBuilder {
+    allowed_formats: Option<Vec<String>>,
+    allowed_models: Option<Vec<String>>,
+    cancellation: Option<Arc<AtomicBool>>,
+    capture_site_info: bool,
+    capture_threads: bool,
+    capture_unknown_page_fields: bool,
+    check_xmlns: bool,
+    collect_warnings: bool,
+    decode_entities: bool,
+    default_namespace: Option<NamespaceId>,
+    detect_duplicate_titles: bool,
+    #[cfg(feature = "encoding_rs")]
+    encoding: Option<&'static encoding_rs::Encoding>,
+    filter: Option<Filter>,
+    expand_empty_elements: bool,
+    lenient_text: bool,
+    max_namespace_buffer: Option<usize>,
+    max_revisions_per_page: Option<usize>,
+    multiple_roots: bool,
+    namespace_range: Option<RangeInclusive<NamespaceId>>,
+    normalize_model: bool,
+    normalize_newlines: bool,
+    on_namespace_change: Option<Callback<NamespaceChangeCallback>>,
+    on_unknown_element: Option<Callback<UnknownElementCallback>>,
+    on_unsupported: Option<Callback<UnsupportedCallback>>,
+    page_range: Option<Range<usize>>,
+    partial_page_errors: bool,
+    raw_namespace: bool,
+    record_empty_text_element: bool,
+    recover_from_errors: bool,
+    resolve_text: Option<Callback<TextResolver>>,
+    schema_versions: Option<Vec<(u32, u32)>>,
+    skip_unmatched: bool,
+    skip_unsupported: bool,
+    strip_html_comments: bool,
+    strip_text_bom: bool,
+    text_contains: Option<Vec<u8>>,
+    text_hint: usize,
+    #[cfg(feature = "sha1")]
+    text_sha1: bool,
+    text_spans: bool,
+    text_transform: Option<Callback<TextTransform>>,
+    title_key: Option<Callback<TitleKeyHasher>>,
+    xmlns: Option<Vec<u8>>,
+}
+
+/// A callback stored in a [`ParserBuilder`].
+struct Callback<F: ?Sized>(Box<F>);
+
+impl<F: ?Sized> std::fmt::Debug for Callback<F> {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("Callback")
+    }
+}
+
+type NamespaceChangeCallback =
+    dyn FnMut(Option<NamespaceId>, NamespaceId) + Send;
+
+type TextResolver = dyn FnMut(&str) -> std::io::Result<String> + Send;
+
+type TextTransform = dyn FnMut(Field, String) -> String + Send;
+
+type TitleKeyHasher = dyn Fn(NamespaceId, &str) -> u64 + Send;
+
+type UnknownElementCallback =
+    dyn FnMut(&str, &[(&[u8], &[u8])], &DecoderCtx) + Send;
+
+type UnsupportedCallback = dyn FnMut(&PartialPage, usize) + Send;
+
+/// Versions of the export schema that are accepted by default.
+///
+/// These are all the versions published at
+/// [mediawiki.org](https://www.mediawiki.org/xml/).
+const KNOWN_SCHEMA_VERSIONS: &[(u32, u32)] = &[
+    (0, 3),
+    (0, 4),
+    (0, 5),
+    (0, 6),
+    (0, 7),
+    (0, 8),
+    (0, 9),
+    (0, 10),
+    (0, 11),
+];
+
+/// Parser working as an iterator over the metadata of pages.
+///
+/// Created by [`parse_meta`] and [`parse_meta_with_namespace`].
+pub struct MetaParser<R: BufRead, Namespace>(Parser<R, Namespace>);
+
+impl<N> Page<N> {
+    /**
+    Empties the page while keeping the capacity of the `text` and `title`
+    fields and of the `extra` and `redirect_attributes` fields, so that the
+    page can be reused without allocating again.
+
+    Optional fields are set to `None` and `deleted` is reset. The namespace
+    is left as it is, since the namespace type has no empty value.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text>beta</text></revision></page>
+    </mediawiki>"#;
+    let mut page = parse_mediawiki_dump::parse(dump.as_bytes()).next().unwrap().unwrap();
+    let capacity = page.text.capacity();
+    page.clear();
+    assert!(page.text.is_empty() && page.title.is_empty());
+    assert_eq!(page.text.capacity(), capacity);
+    ```
+    */
+    pub fn clear(&mut self) {
+        // Destructured so that a new field can't be forgotten here.
+        let Page {
+            declared_bytes,
+            declared_sha1,
+            deleted,
+            extra,
+            format,
+            model,
+            namespace: _,
+            page_id,
+            raw_format,
+            raw_model,
+            raw_namespace,
+            redirect_attributes,
+            redirect_title,
+            sha1,
+            text,
+            #[cfg(feature = "sha1")]
+            text_sha1,
+            text_location,
+            text_span,
+            text_was_empty_element,
+            thread,
+            title,
+            title_key,
+        } = self;
+        *declared_bytes = None;
+        *declared_sha1 = None;
+        *deleted = Deleted::default();
+        extra.clear();
+        *format = None;
+        *model = None;
+        *page_id = None;
+        *raw_format = None;
+        *raw_model = None;
+        *raw_namespace = None;
+        redirect_attributes.clear();
+        *redirect_title = None;
+        *sha1 = None;
+        text.clear();
+        #[cfg(feature = "sha1")]
+        {
+            *text_sha1 = None;
+        }
+        *text_location = None;
+        *text_span = None;
+        *text_was_empty_element = false;
+        *thread = None;
+        title.clear();
+        *title_key = None;
+    }
+
+    /**
+    Returns the namespace and title of the page, which together identify
+    the page.
+
+    The implementations of [`PartialEq`], [`Eq`] and [`Hash`] for `Page`
+    already look only at these fields, but using this pair as the key of
+    a map avoids keeping the rest of the page, notably its text, in the map.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let page = parse_mediawiki_dump::parse(dump.as_bytes()).next().unwrap().unwrap();
+    assert_eq!(page.identity(), (parse_mediawiki_dump::NamespaceId(0), "alpha"));
+    ```
+    */
+    pub fn identity(&self) -> (N, &str)
+    where
+        N: Copy,
+    {
+        (self.namespace, &self.title)
+    }
+
+    /**
+    Returns whether the page is an ordinary article, given the main
+    namespace of the namespace type.
+
+    A page is considered an ordinary article if it is in the main namespace
+    and both the format `text/x-wiki` and the model `wikitext` are given.
+    */
+    pub fn is_content_article_in(&self, main: &N) -> bool
+    where
+        N: PartialEq,
+    {
+        self.namespace == *main
+            && self.format.as_deref() == Some("text/x-wiki")
+            && self.model.as_deref() == Some("wikitext")
+    }
+
+    /**
+    Returns an iterator over the lines of the text of the page, without
+    allocating.
+
+    The text is split on `\n`, and a `\r` before it is removed, as by
+    [`str::lines`]. A line break at the end of the text doesn't start
+    another line.
+
+    ```rust
+    let page = parse_mediawiki_dump::Page::<parse_mediawiki_dump::NamespaceId> {
+        text: "== Heading ==\r\nalpha\n\nbeta\n".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(
+        page.text_lines().collect::<Vec<_>>(),
+        ["== Heading ==", "alpha", "", "beta"]
+    );
+    ```
+    */
+    pub fn text_lines(&self) -> impl Iterator<Item = &str> {
+        self.text.lines()
+    }
+
+    /**
+    Returns the SHA-1 of the text of the revision from the `sha1` field as
+    the 20 bytes of the digest, for comparing it with hashes computed
+    elsewhere.
+
+    The `sha1` field is decoded as a base 36 number, as MediaWiki formats
+    it, with or without the zeros padding it to 31 digits. Returns `None`
+    if the field is absent or isn't a base 36 number that fits in 20
+    bytes.
+
+    ```rust
+    let page = parse_mediawiki_dump::Page::<parse_mediawiki_dump::NamespaceId> {
+        sha1: Some("phoiac9h4m842xq45sp7s6u21eteeq1".to_string()),
+        ..Default::default()
+    };
+    // The SHA-1 of the empty text.
+    assert_eq!(
+        page.sha1_bytes().unwrap()[..4],
+        [0xda, 0x39, 0xa3, 0xee]
+    );
+    ```
+    */
+    pub fn sha1_bytes(&self) -> Option<[u8; 20]> {
+        sha1_from_base36(self.sha1.as_deref()?)
+    }
+}
+
+impl Page<NamespaceId> {
+    /**
+    Returns whether the page is an ordinary article: a page in namespace 0
+    with the format `text/x-wiki` and the model `wikitext`.
+
+    See [`Page::is_content_article_in`] for other namespace types.
+    */
+    pub fn is_content_article(&self) -> bool {
+        self.is_content_article_in(&NamespaceId(0))
+    }
+
+    /**
+    Converts the namespace of the page to another type, moving the other
+    fields.
+
+    This allows parsing with the default namespace type and selecting
+    a custom type later without parsing again. A generic `TryFrom`
+    implementation is not possible because it would conflict with the
+    reflexive implementation in the standard library.
+
+    Returns [`Error::UnknownNamespace`] if the namespace id cannot be
+    converted. Unlike [`Error::Namespace`], it has no position, because the
+    page is no longer associated with a stream.
+    */
+    pub fn try_into_namespace<N: FromNamespaceId>(
+        self,
+    ) -> Result<Page<N>, Error> {
+        let namespace = N::from_namespace_id(self.namespace)
+            .ok_or(Error::UnknownNamespace(self.namespace))?;
+        Ok(self.with_namespace(namespace))
+    }
+
+    /// Replaces the namespace of the page, moving the other fields.
+    fn with_namespace<N>(self, namespace: N) -> Page<N> {
+        Page {
+            declared_bytes: self.declared_bytes,
+            declared_sha1: self.declared_sha1,
+            deleted: self.deleted,
+            extra: self.extra,
+            format: self.format,
+            model: self.model,
+            namespace,
+            page_id: self.page_id,
+            raw_format: self.raw_format,
+            raw_model: self.raw_model,
+            raw_namespace: self.raw_namespace,
+            redirect_attributes: self.redirect_attributes,
+            redirect_title: self.redirect_title,
+            sha1: self.sha1,
+            text: self.text,
+            #[cfg(feature = "sha1")]
+            text_sha1: self.text_sha1,
+            text_location: self.text_location,
+            text_span: self.text_span,
+            text_was_empty_element: self.text_was_empty_element,
+            thread: self.thread,
+            title: self.title,
+            title_key: self.title_key,
+        }
+    }
 }
 
 impl<N> PartialEq for Page<N>
@@ -359,324 +1197,3025 @@ where
     }
 }
 
-impl std::fmt::Display for Error {
+/// Displays where an error within a page occurred, after the error.
+struct InPage<'a>(&'a PartialPage);
+
+impl std::fmt::Display for InPage<'_> {
     fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            Error::Format(position) => {
-                write!(formatter, "Invalid format at position {}", position)
-            }
-            Error::NotSupported(position) => write!(
-                formatter,
-                "The element at position {} is not supported",
-                position
-            ),
-            Error::XmlReader(error) => error.fmt(formatter),
-            Error::Namespace { id, position } => write!(
-                formatter,
-                "The namespace {} at position {} was not recognized",
-                id.into_inner(),
-                position,
-            ),
+        match &self.0.title {
+            Some(title) => write!(formatter, " in page {:?}", title),
+            None => write!(formatter, " in page with unknown title"),
         }
     }
 }
 
-impl std::error::Error for Error {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        if let Self::XmlReader(e) = self {
-            Some(e)
-        } else {
-            None
-        }
-    }
-}
+impl<R: BufRead, N: FromNamespaceId> Iterator for Parser<R, N> {
+    type Item = Result<Page<N>, Error>;
 
-impl From<quick_xml::Error> for Error {
-    fn from(value: quick_xml::Error) -> Self {
-        Error::XmlReader(value)
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match next(self) {
+            Err(error) => Err(stop_after_error(self, error)),
+            Ok(item) => {
+                let page = item?;
+                self.stats
+                    .record(page.redirect_title.is_some(), page.text.len());
+                Ok(page)
+            }
+        })
     }
 }
 
-impl<R: BufRead, N: FromNamespaceId> Iterator for Parser<R, N> {
-    type Item = Result<Page<N>, Error>;
+impl<R: BufRead, N: FromNamespaceId> FusedIterator for Parser<R, N> {}
+
+impl<R: BufRead, N: FromNamespaceId> Iterator for MetaParser<R, N> {
+    type Item = Result<PageMeta<N>, Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        Some(match next(self) {
-            Err(error) => Err(error),
-            Ok(item) => Ok(item?),
+        Some(match next_meta(&mut self.0) {
+            Err(error) => Err(stop_after_error(&mut self.0, error)),
+            Ok(item) => {
+                let page = item?;
+                self.0.stats.record(page.redirect_title.is_some(), 0);
+                Ok(page)
+            }
         })
     }
 }
 
-fn match_namespace(namespace: Option<&[u8]>) -> bool {
-    match namespace {
-        None => false,
-        Some(namespace) => {
-            namespace == b"http://www.mediawiki.org/xml/export-0.10/" as &[u8]
+impl<R: BufRead, N: FromNamespaceId> FusedIterator for MetaParser<R, N> {}
+
+/// Ends the parser after an error, or makes it skip to the next page if
+/// [`ParserBuilder::recover_from_errors`] is enabled, returning the error.
+/// After [`Error::TooManyRevisions`] the rest of the page has already been
+/// skipped, so the parser continues either way.
+fn stop_after_error<R: BufRead, N>(
+    parser: &mut Parser<R, N>,
+    error: Error,
+) -> Error {
+    if parser.options.recover_from_errors {
+        parser.recovering = true;
+    } else if !matches!(error, Error::TooManyRevisions { .. }) {
+        parser.ended = true;
+    }
+    error
+}
+
+/// Parses the version from the XML namespace of an export schema,
+/// `http://www.mediawiki.org/xml/export-X.Y/`.
+fn schema_version(namespace: &[u8]) -> Option<(u32, u32)> {
+    let version = namespace
+        .strip_prefix(b"http://www.mediawiki.org/xml/export-")?
+        .strip_suffix(b"/")?;
+    let version = std::str::from_utf8(version).ok()?;
+    let mut parts = version.splitn(2, '.');
+    Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+}
+
+/// Reads the next event into the buffer of the parser. The first element of
+/// the result tells whether the element of a start or end tag is in the XML
+/// namespace of an accepted schema version.
+///
+/// A macro rather than a method so that the event only borrows the buffer.
+macro_rules! read_event {
+    ($parser:expr) => {
+        next_event(
+            &mut $parser.reader,
+            &mut $parser.buffer,
+            &mut $parser.namespace_buffer,
+            &$parser.options,
+        )
+    };
+}
+
+/// Fields of a `page` element collected so far.
+struct PageState<N> {
+    declared_bytes: Option<u64>,
+    declared_sha1: Option<String>,
+    deleted: Deleted,
+    extra: HashMap<String, String>,
+    format: Option<String>,
+    /// Whether a `format` element has been parsed, even if it was empty.
+    format_seen: bool,
+    id: Option<u64>,
+    /// Whether the revision has a `minor` element.
+    minor: bool,
+    model: Option<String>,
+    /// Whether a `model` element has been parsed, even if it was empty.
+    model_seen: bool,
+    namespace: Option<N>,
+    namespace_id: Option<NamespaceId>,
+    raw_format: Option<String>,
+    raw_model: Option<String>,
+    raw_namespace: Option<String>,
+    redirect_attributes: Vec<(String, String)>,
+    redirect_title: Option<String>,
+    /// Whether a `revision` element has been read, even if it was empty.
+    revision_seen: bool,
+    sha1: Option<String>,
+    text: Option<String>,
+    #[cfg(feature = "sha1")]
+    text_sha1: Option<String>,
+    text_location: Option<String>,
+    text_span: Option<(usize, usize)>,
+    /// Whether the text doesn't contain the string set with
+    /// [`ParserBuilder::text_contains`].
+    text_unmatched: bool,
+    text_was_empty_element: bool,
+    thread: Option<Thread>,
+    timestamp: Option<String>,
+    title: Option<String>,
+}
+
+impl<N> PageState<N> {
+    fn new() -> Self {
+        Self {
+            declared_bytes: None,
+            declared_sha1: None,
+            deleted: Deleted::default(),
+            extra: HashMap::new(),
+            format: None,
+            format_seen: false,
+            id: None,
+            minor: false,
+            model: None,
+            model_seen: false,
+            namespace: None,
+            namespace_id: None,
+            raw_format: None,
+            raw_model: None,
+            raw_namespace: None,
+            redirect_attributes: Vec::new(),
+            redirect_title: None,
+            revision_seen: false,
+            sha1: None,
+            text: None,
+            #[cfg(feature = "sha1")]
+            text_sha1: None,
+            text_location: None,
+            text_span: None,
+            text_unmatched: false,
+            text_was_empty_element: false,
+            thread: None,
+            timestamp: None,
+            title: None,
+        }
+    }
+
+    /// Replaces the namespace of the page, moving the other fields.
+    fn with_namespace<M>(self, namespace: Option<M>) -> PageState<M> {
+        PageState {
+            declared_bytes: self.declared_bytes,
+            declared_sha1: self.declared_sha1,
+            deleted: self.deleted,
+            extra: self.extra,
+            format: self.format,
+            format_seen: self.format_seen,
+            id: self.id,
+            minor: self.minor,
+            model: self.model,
+            model_seen: self.model_seen,
+            namespace,
+            namespace_id: self.namespace_id,
+            raw_format: self.raw_format,
+            raw_model: self.raw_model,
+            raw_namespace: self.raw_namespace,
+            redirect_attributes: self.redirect_attributes,
+            redirect_title: self.redirect_title,
+            revision_seen: self.revision_seen,
+            sha1: self.sha1,
+            text: self.text,
+            #[cfg(feature = "sha1")]
+            text_sha1: self.text_sha1,
+            text_location: self.text_location,
+            text_span: self.text_span,
+            text_unmatched: self.text_unmatched,
+            text_was_empty_element: self.text_was_empty_element,
+            thread: self.thread,
+            timestamp: self.timestamp,
+            title: self.title,
         }
     }
 }
 
+/// Takes the fields of the page read by [`Parser::next_title`] if any, to
+/// parse the rest of the page.
+fn take_current_page<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+) -> Option<PageState<N>> {
+    let page = parser.current_page.take()?;
+    let namespace = page.namespace_id.and_then(N::from_namespace_id);
+    Some(page.with_namespace(namespace))
+}
+
 fn next<R: BufRead, N: FromNamespaceId>(
     parser: &mut Parser<R, N>,
 ) -> Result<Option<Page<N>>, Error> {
-    if !parser.started {
-        loop {
-            parser.buffer.clear();
-            if let (namespace, Event::Start(event)) =
-                parser.reader.read_namespaced_event(
-                    &mut parser.buffer,
-                    &mut parser.namespace_buffer,
-                )?
-            {
-                if match_namespace(namespace)
-                    && event.local_name() == b"mediawiki"
-                {
-                    break;
-                }
-                return Err(Error::Format(parser.reader.buffer_position()));
-            }
+    let page = loop {
+        let page = match take_current_page(parser) {
+            Some(page) => page,
+            None if next_page_start(parser)? => PageState::new(),
+            None => return Ok(None),
+        };
+        if let Some(page) = parse_page(parser, page, true)? {
+            break page;
+        }
+    };
+    update_shared_position(parser);
+    change_namespace(parser, page.namespace_id);
+    check_duplicate_title(parser, &page)?;
+    match page {
+        PageState {
+            declared_bytes,
+            declared_sha1,
+            deleted,
+            extra,
+            format,
+            id,
+            model,
+            namespace: Some(namespace),
+            namespace_id,
+            raw_format,
+            raw_model,
+            raw_namespace,
+            redirect_attributes,
+            redirect_title,
+            sha1,
+            text: Some(text),
+            #[cfg(feature = "sha1")]
+            text_sha1,
+            text_location,
+            text_span,
+            text_was_empty_element,
+            thread,
+            title: Some(title),
+            ..
+        } => Ok(Some(Page {
+            title_key: match (&parser.options.title_key, namespace_id) {
+                (Some(hasher), Some(id)) => Some((hasher.0)(id, &title)),
+                _ => None,
+            },
+            declared_bytes,
+            declared_sha1,
+            deleted,
+            extra,
+            format,
+            model,
+            namespace,
+            page_id: id,
+            raw_format,
+            raw_model,
+            raw_namespace,
+            redirect_attributes,
+            redirect_title,
+            sha1,
+            text,
+            #[cfg(feature = "sha1")]
+            text_sha1,
+            text_location,
+            text_span,
+            text_was_empty_element,
+            thread,
+            title,
+        })),
+        page => {
+            let error = Error::Format(parser.reader.buffer_position());
+            Err(page_error(parser, &page, error))
         }
-        parser.started = true;
     }
-    loop {
-        parser.buffer.clear();
-        if !match parser.reader.read_namespaced_event(
-            &mut parser.buffer,
-            &mut parser.namespace_buffer,
-        )? {
-            (_, Event::End(_)) => return Ok(None),
-            (namespace, Event::Start(event)) => {
-                match_namespace(namespace) && event.local_name() == b"page"
-            }
-            _ => continue,
-        } {
-            skip_element(parser)?;
-            continue;
-        }
-        let mut format = None;
-        let mut model = None;
-        let mut namespace = None;
-        let mut redirect_title = None;
-        let mut text = None;
-        let mut title = None;
-        loop {
-            parser.buffer.clear();
-            match match parser.reader.read_namespaced_event(
-                &mut parser.buffer,
-                &mut parser.namespace_buffer,
-            )? {
-                (_, Event::End(_)) => {
-                    return match (namespace, text, title) {
-                        (Some(namespace), Some(text), Some(title)) => {
-                            Ok(Some(Page {
-                                format,
-                                model,
-                                namespace,
-                                redirect_title,
-                                text,
-                                title,
-                            }))
-                        }
-                        _ => {
-                            Err(Error::Format(parser.reader.buffer_position()))
-                        }
-                    }
-                }
-                (namespace, Event::Start(event)) => {
-                    if match_namespace(namespace) {
-                        match event.local_name() {
-                            b"ns" => PageChildElement::Ns,
-                            b"redirect" => {
-                                let title_attribute = event
-                                    .attributes()
-                                    .filter_map(|r| r.ok())
-                                    .find(|attr| attr.key == b"title");
-                                redirect_title = match title_attribute {
-                                    Some(attr) => {
-                                        Some(attr.unescape_and_decode_value(
-                                            &parser.reader,
-                                        )?)
-                                    }
-                                    None => {
-                                        return Err(Error::Format(
-                                            parser.reader.buffer_position(),
-                                        ))
-                                    }
-                                };
-                                PageChildElement::Redirect
-                            }
-                            b"revision" => PageChildElement::Revision,
-                            b"title" => PageChildElement::Title,
-                            _ => PageChildElement::Unknown,
-                        }
-                    } else {
-                        PageChildElement::Unknown
-                    }
+}
+
+fn next_meta<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+) -> Result<Option<PageMeta<N>>, Error> {
+    let page = loop {
+        let page = match take_current_page(parser) {
+            Some(page) => page,
+            None if next_page_start(parser)? => PageState::new(),
+            None => return Ok(None),
+        };
+        if let Some(page) = parse_page(parser, page, false)? {
+            break page;
+        }
+    };
+    update_shared_position(parser);
+    change_namespace(parser, page.namespace_id);
+    check_duplicate_title(parser, &page)?;
+    match page {
+        PageState {
+            id,
+            namespace: Some(namespace),
+            redirect_title,
+            title: Some(title),
+            ..
+        } => Ok(Some(PageMeta {
+            page_id: id,
+            namespace,
+            redirect_title,
+            title,
+        })),
+        page => {
+            let error = Error::Format(parser.reader.buffer_position());
+            Err(page_error(parser, &page, error))
+        }
+    }
+}
+
+/// Records the namespace of a page about to be returned, calling the function
+/// set with [`ParserBuilder::on_namespace_change`] if it differs from the
+/// namespace of the previous page.
+fn change_namespace<R: BufRead, N>(
+    parser: &mut Parser<R, N>,
+    namespace: Option<NamespaceId>,
+) {
+    let namespace = match namespace {
+        Some(namespace) if Some(namespace) != parser.last_namespace => {
+            namespace
+        }
+        _ => return,
+    };
+    let previous = parser.last_namespace.replace(namespace);
+    if let Some(Callback(callback)) = &mut parser.options.on_namespace_change {
+        callback(previous, namespace);
+    }
+}
+
+/// Records the namespace and title of a page about to be returned if enabled
+/// with [`ParserBuilder::detect_duplicate_titles`], returning an error or
+/// recording a warning if they have been seen before.
+fn check_duplicate_title<R: BufRead, N>(
+    parser: &mut Parser<R, N>,
+    page: &PageState<N>,
+) -> Result<(), Error> {
+    let key = match (page.namespace_id, &page.title) {
+        (Some(namespace), Some(title))
+            if parser.options.detect_duplicate_titles =>
+        {
+            (namespace.into_inner(), title.clone())
+        }
+        _ => return Ok(()),
+    };
+    if parser.seen_titles.insert(key) {
+        return Ok(());
+    }
+    let title = page.title.clone().unwrap_or_default();
+    let position = parser.reader.buffer_position();
+    if parser.options.collect_warnings {
+        warn(parser, Warning::DuplicateTitle { title, position });
+        Ok(())
+    } else {
+        let error = Error::DuplicateTitle { title, position };
+        Err(page_error(parser, page, error))
+    }
+}
+
+/// Reads up to and including the start tag of the next `page` element within
+/// the range set with [`ParserBuilder::page_range`], skipping any other
+/// elements. Returns `false` at the end of the root element or of the range.
+fn next_page_start<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+) -> Result<bool, Error> {
+    loop {
+        if let Some(range) = &parser.options.page_range {
+            if parser.page_index >= range.end {
+                parser.past_range = true;
+            }
+        }
+        if !next_page_start_tag(parser)? {
+            return Ok(false);
+        }
+        let index = parser.page_index;
+        parser.page_index += 1;
+        match &parser.options.page_range {
+            Some(range) if index < range.start => skip_element(parser)?,
+            _ => return Ok(true),
+        }
+    }
+}
+
+/// Reads up to and including the start tag of the next `page` element,
+/// skipping any other elements. Returns `false` at the end of the root element.
+fn next_page_start_tag<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+) -> Result<bool, Error> {
+    update_shared_position(parser);
+    if parser.ended || parser.past_range {
+        return Ok(false);
+    }
+    if let Some(cancellation) = &parser.options.cancellation {
+        if cancellation.load(Ordering::Relaxed) {
+            parser.ended = true;
+            return Err(Error::Cancelled {
+                position: parser.reader.buffer_position(),
+            });
+        }
+    }
+    if let Some(capacity) = parser.options.max_namespace_buffer {
+        parser.namespace_buffer.shrink_to(capacity);
+    }
+    'root: loop {
+        if !parser.started {
+            loop {
+                match read_event!(parser)? {
+                    (accepted, Event::Start(event)) => {
+                        if accepted && event.local_name() == b"mediawiki" {
+                            if parser.options.collect_warnings {
+                                if let Some(namespace) =
+                                    unknown_schema(&parser.options, &event)?
+                                {
+                                    let position =
+                                        parser.reader.buffer_position();
+                                    parser.warnings.push(
+                                        Warning::UnknownSchema {
+                                            namespace,
+                                            position,
+                                        },
+                                    );
+                                }
+                            }
+                            break;
+                        }
+                    }
+                    (accepted, Event::Empty(event)) => {
+                        if accepted && event.local_name() == b"mediawiki" {
+                            if parser.options.multiple_roots {
+                                continue;
+                            }
+                            parser.ended = true;
+                            update_shared_position(parser);
+                            return Ok(false);
+                        }
+                    }
+                    (_, Event::Eof) if parser.options.multiple_roots => {
+                        parser.ended = true;
+                        update_shared_position(parser);
+                        return Ok(false);
+                    }
+                    (_, Event::Eof) => {
+                        parser.ended = true;
+                        return Err(Error::UnexpectedEof(
+                            parser.reader.buffer_position(),
+                        ));
+                    }
+                    _ => continue,
+                }
+                return Err(Error::Format(parser.reader.buffer_position()));
+            }
+            parser.started = true;
+        }
+        loop {
+            parser.page_start = parser.reader.buffer_position();
+            if parser.page_start_read {
+                parser.page_start_read = false;
+                parser.recovering = false;
+                return Ok(true);
+            }
+            if match read_event!(parser)? {
+                (accepted, Event::End(event)) => {
+                    // When recovering, the end tag may be of an element
+                    // within the page where the error occurred.
+                    if parser.recovering
+                        && !(accepted && event.local_name() == b"mediawiki")
+                    {
+                        continue;
+                    }
+                    if parser.options.multiple_roots {
+                        parser.started = false;
+                        continue 'root;
+                    }
+                    parser.ended = true;
+                    update_shared_position(parser);
+                    return Ok(false);
+                }
+                (accepted, Event::Start(event)) => {
+                    if accepted
+                        && parser.options.capture_site_info
+                        && event.local_name() == b"siteinfo"
+                    {
+                        parser.site_info = Some(parse_site_info(parser)?);
+                        continue;
+                    }
+                    accepted && event.local_name() == b"page"
+                }
+                (accepted, Event::Empty(event)) => {
+                    if accepted && event.local_name() == b"page" {
+                        return Err(Error::Format(
+                            parser.reader.buffer_position(),
+                        ));
+                    }
+                    continue;
+                }
+                (_, Event::Eof) => {
+                    parser.ended = true;
+                    return Err(Error::UnexpectedEof(
+                        parser.reader.buffer_position(),
+                    ));
                 }
                 _ => continue,
             } {
-                PageChildElement::Ns => {
-                    match parse_text(parser, &namespace)?.parse::<NamespaceId>()
-                    {
-                        Err(_) => {
-                            return Err(Error::Format(
+                parser.recovering = false;
+                return Ok(true);
+            }
+            skip_element(parser)?;
+        }
+    }
+}
+
+/// Returns the XML namespace of the root element if it is neither the
+/// namespace of a version of the export schema known to this crate nor
+/// the namespace set with [`ParserBuilder::xmlns`].
+fn unknown_schema(
+    options: &ParserBuilder,
+    event: &BytesStart,
+) -> Result<Option<String>, Error> {
+    let namespace = event
+        .attributes()
+        .filter_map(|r| r.ok())
+        .find(|attribute| attribute.key == b"xmlns")
+        .map(|attribute| attribute.value);
+    if options.xmlns.is_some()
+        && namespace.as_deref() == options.xmlns.as_deref()
+    {
+        return Ok(None);
+    }
+    match namespace.as_deref().and_then(schema_version) {
+        Some(version) if KNOWN_SCHEMA_VERSIONS.contains(&version) => Ok(None),
+        _ => Ok(Some(unescape_and_decode(
+            namespace.as_deref().unwrap_or(b""),
+            options,
+        )?)),
+    }
+}
+
+/// Parses the children of a `page` element up to its end tag, adding them to
+/// the fields already in `page`. If `revisions` is `false`, `revision`
+/// elements are skipped without reading their content. Returns `None` if the
+/// page was skipped.
+fn parse_page<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+    mut page: PageState<N>,
+    revisions: bool,
+) -> Result<Option<PageState<N>>, Error> {
+    let result = match parse_page_children(parser, &mut page, revisions) {
+        Err(error) => Err(page_error(parser, &page, error)),
+        Ok(false) => Ok(None),
+        Ok(true) => match default_namespace(parser, &mut page) {
+            Err(error) => Err(page_error(parser, &page, error)),
+            Ok(()) if !namespace_in_range(parser, &page) => Ok(None),
+            Ok(()) if !filter_accepts(&parser.options, &page, true) => Ok(None),
+            Ok(()) if page.text_unmatched && parser.options.skip_unmatched => {
+                Ok(None)
+            }
+            Ok(()) => return Ok(Some(page)),
+        },
+    };
+    // Gives back the buffer given to `Parser::next_text_into` for the next
+    // page, without the text of this page.
+    if let (Some(length), Some(mut text)) =
+        (parser.text_buffer_length, page.text)
+    {
+        text.truncate(length);
+        parser.text_buffer = text;
+    }
+    result
+}
+
+/// Returns whether the namespace of a page is within the range set with
+/// [`ParserBuilder::namespace_range`], or is not known yet. Stops the parser
+/// if the namespace is past the range.
+fn namespace_in_range<R: BufRead, N>(
+    parser: &mut Parser<R, N>,
+    page: &PageState<N>,
+) -> bool {
+    match (&parser.options.namespace_range, page.namespace_id) {
+        (Some(range), Some(id)) => {
+            if id > *range.end() {
+                parser.past_range = true;
+            }
+            range.contains(&id)
+        }
+        _ => true,
+    }
+}
+
+/// Sets the namespace of a page without an `ns` element to the default
+/// namespace if one is set.
+fn default_namespace<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+    page: &mut PageState<N>,
+) -> Result<(), Error> {
+    if let (None, Some(id)) =
+        (&page.namespace, parser.options.default_namespace)
+    {
+        let namespace = N::from_namespace_id(id).ok_or(Error::Namespace {
+            id,
+            position: parser.reader.buffer_position(),
+        })?;
+        page.namespace_id = Some(id);
+        page.namespace = Some(namespace);
+        let position = parser.reader.buffer_position();
+        warn(
+            parser,
+            Warning::DefaultNamespace {
+                title: page.title.clone(),
+                position,
+            },
+        );
+    }
+    Ok(())
+}
+
+fn partial_page<N>(page: &PageState<N>) -> PartialPage {
+    PartialPage {
+        namespace: page.namespace_id,
+        title: page.title.clone(),
+    }
+}
+
+/// Wraps an error that occurred within a page with the fields parsed so far
+/// if enabled with [`ParserBuilder::partial_page_errors`].
+fn page_error<R: BufRead, N>(
+    parser: &Parser<R, N>,
+    page: &PageState<N>,
+    error: Error,
+) -> Error {
+    if parser.options.partial_page_errors {
+        Error::Page {
+            page: partial_page(page),
+            error: Box::new(error),
+        }
+    } else {
+        error
+    }
+}
+
+fn parse_page_children<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+    page: &mut PageState<N>,
+    revisions: bool,
+) -> Result<bool, Error> {
+    loop {
+        match read_page_child(parser, page)? {
+            None => return Ok(true),
+            Some((PageChildElement::Revision, empty)) => {
+                if !page_revision(parser, page, empty, revisions)? {
+                    return Ok(false);
+                }
+            }
+            Some((element, empty)) => {
+                parse_page_child(parser, page, element, empty)?;
+                if !namespace_in_range(parser, page)
+                    || !filter_accepts(&parser.options, page, false)
+                {
+                    skip_element(parser)?;
+                    return Ok(false);
+                }
+            }
+        }
+    }
+}
+
+/// Parses or skips a `revision` element whose start tag has been read, as
+/// a child of a `page` element. Returns `false` if the page was skipped
+/// because it has more than one revision, as enabled with
+/// [`ParserBuilder::skip_unsupported`].
+fn page_revision<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+    page: &mut PageState<N>,
+    empty: bool,
+    parse: bool,
+) -> Result<bool, Error> {
+    if page.revision_seen {
+        let position = parser.reader.buffer_position();
+        if let Some(callback) = &mut parser.options.on_unsupported {
+            (callback.0)(&partial_page(page), position);
+        }
+        if parser.options.skip_unsupported {
+            warn(
+                parser,
+                Warning::SkippedUnsupported {
+                    page: partial_page(page),
+                    position,
+                },
+            );
+            if !empty {
+                skip_element(parser)?;
+            }
+            skip_element(parser)?;
+            return Ok(false);
+        }
+        return Err(Error::NotSupported(position));
+    }
+    page.revision_seen = true;
+    if !empty {
+        if parse {
+            parse_revision(parser, page)?;
+        } else {
+            skip_element(parser)?;
+        }
+    }
+    Ok(true)
+}
+
+/// Reads up to and including the start tag of the next child of a `page`
+/// element, returning the kind of element and whether it is an empty
+/// element. The attributes of a `redirect` element are stored in `page`.
+/// Returns `None` at the end tag of the page.
+fn read_page_child<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+    page: &mut PageState<N>,
+) -> Result<Option<(PageChildElement, bool)>, Error> {
+    loop {
+        let (accepted, event) = read_event!(parser)?;
+        let empty = matches!(event, Event::Empty(_));
+        let element = match event {
+            Event::End(_) => return Ok(None),
+            Event::Start(event) | Event::Empty(event) => {
+                if accepted {
+                    match event.local_name() {
+                        b"id" => PageChildElement::Id,
+                        b"ns" => PageChildElement::Ns,
+                        b"redirect" => {
+                            let mut title = None;
+                            for attribute in
+                                event.attributes().filter_map(|r| r.ok())
+                            {
+                                let value = unescape_and_decode(
+                                    &attribute.value,
+                                    &parser.options,
+                                )?;
+                                if attribute.key == b"title" {
+                                    title = Some(value);
+                                } else {
+                                    page.redirect_attributes.push((
+                                        decode_raw(
+                                            attribute.key,
+                                            &parser.options,
+                                        )?
+                                        .into_owned(),
+                                        value,
+                                    ));
+                                }
+                            }
+                            match title {
+                                Some(title) => {
+                                    page.redirect_title = Some(transform(
+                                        parser,
+                                        Field::RedirectTitle,
+                                        title,
+                                    ))
+                                }
+                                None if page.redirect_title.is_none() => {
+                                    return Err(Error::Format(
+                                        parser.reader.buffer_position(),
+                                    ))
+                                }
+                                None => {}
+                            }
+                            PageChildElement::Redirect
+                        }
+                        b"revision" => PageChildElement::Revision,
+                        b"title" => PageChildElement::Title,
+                        name if parser.options.capture_threads
+                            && name.eq_ignore_ascii_case(
+                                b"DiscussionThreading",
+                            ) =>
+                        {
+                            PageChildElement::DiscussionThreading
+                        }
+                        name if parser.options.capture_unknown_page_fields => {
+                            PageChildElement::Extra(
+                                decode_raw(name, &parser.options)?.into_owned(),
+                            )
+                        }
+                        _ => {
+                            unknown_element(
                                 parser.reader.buffer_position(),
-                            ))
+                                &mut parser.options,
+                                &event,
+                            )?;
+                            PageChildElement::Unknown
                         }
-                        Ok(value) => {
-                            namespace =
-                                Some(N::from_namespace_id(value).ok_or_else(
-                                    || Error::Namespace {
-                                        id: value,
-                                        position:
+                    }
+                } else {
+                    unknown_element(
+                        parser.reader.buffer_position(),
+                        &mut parser.options,
+                        &event,
+                    )?;
+                    PageChildElement::Unknown
+                }
+            }
+            Event::Eof => {
+                return Err(Error::UnexpectedEof(
+                    parser.reader.buffer_position(),
+                ))
+            }
+            _ => continue,
+        };
+        return Ok(Some((element, empty)));
+    }
+}
+
+/// Parses a child of a `page` element other than `revision` after its start
+/// tag, up to and including its end tag.
+fn parse_page_child<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+    page: &mut PageState<N>,
+    element: PageChildElement,
+    empty: bool,
+) -> Result<(), Error> {
+    match element {
+        PageChildElement::DiscussionThreading => {
+            if page.thread.is_some() {
+                return Err(Error::Format(parser.reader.buffer_position()));
+            }
+            page.thread = Some(thread::parse_thread(parser, empty)?);
+        }
+        PageChildElement::Extra(name) => {
+            let value = parse_text_chunks(
+                parser,
+                &None::<()>,
+                empty,
+                0,
+                true,
+                String::new(),
+                |_| {},
+            )?;
+            page.extra.insert(name, value);
+        }
+        PageChildElement::Id => {
+            match parse_text(parser, &page.id, empty)?.parse() {
+                Err(_) => {
+                    return Err(Error::Format(parser.reader.buffer_position()))
+                }
+                Ok(value) => page.id = Some(value),
+            }
+        }
+        PageChildElement::Ns => {
+            let text = parse_text(parser, &page.namespace, empty)?;
+            match text.trim().parse::<NamespaceId>() {
+                Err(_) => {
+                    return Err(Error::InvalidNamespaceId {
+                        value: text,
+                        position: parser.reader.buffer_position(),
+                    })
+                }
+                Ok(value) => {
+                    if parser.options.raw_namespace {
+                        page.raw_namespace = Some(text);
+                    }
+                    page.namespace_id = Some(value);
+                    page.namespace =
+                        Some(N::from_namespace_id(value).ok_or_else(|| {
+                            Error::Namespace {
+                                id: value,
+                                position: parser.reader.buffer_position(),
+                            }
+                        })?);
+                }
+            }
+        }
+        PageChildElement::Title => {
+            let title = parse_text(parser, &page.title, empty)?;
+            let title = strip_bom(&parser.options, title);
+            page.title = Some(transform(parser, Field::Title, title));
+        }
+        PageChildElement::Redirect
+        | PageChildElement::Revision
+        | PageChildElement::Unknown => {
+            if !empty {
+                skip_element(parser)?
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Parses the children of a `revision` element up to its end tag.
+fn parse_revision<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+    page: &mut PageState<N>,
+) -> Result<(), Error> {
+    loop {
+        let mut deleted = false;
+        let mut location = None;
+        let mut bytes = None;
+        let mut sha1 = None;
+        let (accepted, event) = read_event!(parser)?;
+        let empty = matches!(event, Event::Empty(_));
+        match match event {
+            Event::End(_) => match page.text {
+                None => {
+                    return Err(Error::Format(parser.reader.buffer_position()))
+                }
+                Some(_) => return Ok(()),
+            },
+            Event::Start(event) | Event::Empty(event) => {
+                if accepted {
+                    // The `bytes`, `location` and `sha1` attributes are only
+                    // defined for the `text` element.
+                    let text = event.local_name() == b"text";
+                    for attribute in event.attributes().filter_map(|r| r.ok()) {
+                        match attribute.key {
+                            b"bytes" if text => {
+                                let value = decode_raw(
+                                    &attribute.value,
+                                    &parser.options,
+                                )?;
+                                match value.parse() {
+                                    Err(_) => {
+                                        return Err(Error::Format(
                                             parser.reader.buffer_position(),
-                                    },
-                                )?);
-                            continue;
+                                        ))
+                                    }
+                                    Ok(value) => bytes = Some(value),
+                                }
+                            }
+                            b"deleted" => {
+                                deleted = &*attribute.value == b"deleted"
+                            }
+                            b"location" if text => {
+                                location = Some(unescape_and_decode(
+                                    &attribute.value,
+                                    &parser.options,
+                                )?)
+                            }
+                            b"sha1" if text => {
+                                sha1 = Some(unescape_and_decode(
+                                    &attribute.value,
+                                    &parser.options,
+                                )?)
+                            }
+                            _ => {}
                         }
                     }
+                    match event.local_name() {
+                        b"comment" => RevisionChildElement::Comment,
+                        b"contributor" => RevisionChildElement::Contributor,
+                        b"format" => RevisionChildElement::Format,
+                        b"minor" => RevisionChildElement::Minor,
+                        b"model" => RevisionChildElement::Model,
+                        b"sha1" => RevisionChildElement::Sha1,
+                        b"text" => RevisionChildElement::Text,
+                        b"timestamp" => RevisionChildElement::Timestamp,
+                        _ => {
+                            unknown_element(
+                                parser.reader.buffer_position(),
+                                &mut parser.options,
+                                &event,
+                            )?;
+                            RevisionChildElement::Unknown
+                        }
+                    }
+                } else {
+                    unknown_element(
+                        parser.reader.buffer_position(),
+                        &mut parser.options,
+                        &event,
+                    )?;
+                    RevisionChildElement::Unknown
+                }
+            }
+            Event::Eof => {
+                return Err(Error::UnexpectedEof(
+                    parser.reader.buffer_position(),
+                ))
+            }
+            _ => continue,
+        } {
+            RevisionChildElement::Comment => {
+                page.deleted.comment = deleted;
+                if !empty {
+                    skip_element(parser)?
+                }
+            }
+            RevisionChildElement::Contributor => {
+                page.deleted.contributor = deleted;
+                if !empty {
+                    skip_element(parser)?
+                }
+            }
+            RevisionChildElement::Format => {
+                if page.format_seen {
+                    return Err(Error::Format(parser.reader.buffer_position()));
+                }
+                page.format_seen = true;
+                let format = parse_text(parser, &page.format, empty)?;
+                // An element that is empty or contains only whitespace is
+                // treated as if it were absent.
+                if format.trim().is_empty() {
+                    continue;
+                }
+                let format = transform(parser, Field::Format, format);
+                let format = if parser.options.normalize_model {
+                    let normalized = normalize_model(&format);
+                    page.raw_format = Some(format);
+                    normalized
+                } else {
+                    format
+                };
+                if !is_allowed(&parser.options.allowed_formats, &format) {
+                    return Err(Error::UnexpectedFormat {
+                        format,
+                        position: parser.reader.buffer_position(),
+                    });
                 }
-                PageChildElement::Redirect => skip_element(parser)?,
-                PageChildElement::Revision => {
-                    if text.is_some() {
-                        return Err(Error::NotSupported(
+                page.format = Some(format);
+            }
+            RevisionChildElement::Model => {
+                if page.model_seen {
+                    return Err(Error::Format(parser.reader.buffer_position()));
+                }
+                page.model_seen = true;
+                let model = parse_text(parser, &page.model, empty)?;
+                // An element that is empty or contains only whitespace is
+                // treated as if it were absent.
+                if model.trim().is_empty() {
+                    continue;
+                }
+                let model = transform(parser, Field::Model, model);
+                let model = if parser.options.normalize_model {
+                    let normalized = normalize_model(&model);
+                    page.raw_model = Some(model);
+                    normalized
+                } else {
+                    model
+                };
+                if !is_allowed(&parser.options.allowed_models, &model) {
+                    return Err(Error::UnexpectedModel {
+                        model,
+                        position: parser.reader.buffer_position(),
+                    });
+                }
+                page.model = Some(model);
+            }
+            RevisionChildElement::Minor => {
+                page.minor = true;
+                if !empty {
+                    skip_element(parser)?
+                }
+            }
+            RevisionChildElement::Sha1 => {
+                page.sha1 = Some(parse_text(parser, &page.sha1, empty)?)
+            }
+            RevisionChildElement::Text => {
+                page.deleted.text = deleted;
+                page.text_location = location;
+                page.declared_bytes = bytes;
+                page.declared_sha1 = sha1;
+                page.text_was_empty_element =
+                    parser.options.record_empty_text_element && empty;
+                if !filter_accepts(&parser.options, page, false) {
+                    // The page is skipped after the revision.
+                    if page.text.is_some() {
+                        return Err(Error::Format(
+                            parser.reader.buffer_position(),
+                        ));
+                    }
+                    if !empty {
+                        skip_element(parser)?;
+                    }
+                    page.text = Some(String::new());
+                } else if parser.options.text_spans {
+                    if page.text.is_some() {
+                        return Err(Error::Format(
                             parser.reader.buffer_position(),
                         ));
                     }
-                    loop {
-                        parser.buffer.clear();
-                        match match parser.reader.read_namespaced_event(
-                            &mut parser.buffer,
-                            &mut parser.namespace_buffer,
-                        )? {
-                            (_, Event::End(_)) => match text {
-                                None => {
-                                    return Err(Error::Format(
-                                        parser.reader.buffer_position(),
-                                    ))
-                                }
-                                Some(_) => break,
-                            },
-                            (namespace, Event::Start(event)) => {
-                                if match_namespace(namespace) {
-                                    match event.local_name() {
-                                        b"format" => {
-                                            RevisionChildElement::Format
-                                        }
-                                        b"model" => RevisionChildElement::Model,
-                                        b"text" => RevisionChildElement::Text,
-                                        _ => RevisionChildElement::Unknown,
-                                    }
-                                } else {
-                                    RevisionChildElement::Unknown
-                                }
-                            }
-                            _ => continue,
-                        } {
-                            RevisionChildElement::Format => {
-                                format = Some(parse_text(parser, &format)?)
-                            }
-                            RevisionChildElement::Model => {
-                                model = Some(parse_text(parser, &model)?)
-                            }
-                            RevisionChildElement::Text => {
-                                text = Some(parse_text(parser, &text)?)
-                            }
-                            RevisionChildElement::Unknown => {
-                                skip_element(parser)?
-                            }
-                        }
-                    }
-                    continue;
-                }
-                PageChildElement::Title => {
-                    title = Some(parse_text(parser, &title)?);
-                    continue;
+                    page.text_span = Some(if empty {
+                        let position = parser.reader.buffer_position();
+                        (position, position)
+                    } else {
+                        skip_text(parser)?
+                    });
+                    page.text = Some(String::new());
+                } else {
+                    let start = parser.text_buffer.len();
+                    let mut text = parse_revision_text(parser, page, empty)?;
+                    // Only the text of the page is processed, not what the
+                    // buffer given to `Parser::next_text_into` contained.
+                    page.text = Some(if start == 0 {
+                        finish_text(parser, page, text)?
+                    } else {
+                        let page_text = text.split_off(start);
+                        text.push_str(&finish_text(parser, page, page_text)?);
+                        text
+                    });
+                }
+            }
+            RevisionChildElement::Timestamp => {
+                page.timestamp =
+                    Some(parse_text(parser, &page.timestamp, empty)?)
+            }
+            RevisionChildElement::Unknown => {
+                if !empty {
+                    skip_element(parser)?
+                }
+            }
+        }
+    }
+}
+
+/// Processes the text of a page after it has been parsed, as enabled with the
+/// options of the parser.
+fn finish_text<R: BufRead, N>(
+    parser: &mut Parser<R, N>,
+    page: &PageState<N>,
+    text: String,
+) -> Result<String, Error> {
+    let mut text = strip_bom(&parser.options, text);
+    if let (false, true, Some(location), Some(resolver)) = (
+        page.text_unmatched,
+        text.is_empty(),
+        &page.text_location,
+        &mut parser.options.resolve_text,
+    ) {
+        let position = parser.reader.buffer_position();
+        text = (resolver.0)(location)
+            .map_err(|source| Error::Io { source, position })?;
+    }
+    if parser.options.normalize_newlines {
+        text = normalize_newlines(text);
+    }
+    if parser.options.strip_html_comments {
+        text = strip_html_comments(text);
+    }
+    Ok(transform(parser, Field::Text, text))
+}
+
+/// Removes a byte order mark from the start of a text or title if enabled.
+fn strip_bom(options: &ParserBuilder, mut text: String) -> String {
+    if options.strip_text_bom && text.starts_with('\u{feff}') {
+        text.drain(..'\u{feff}'.len_utf8());
+    }
+    text
+}
+
+/// Replaces each CRLF and each CR not followed by LF in a text with LF,
+/// without allocating.
+fn normalize_newlines(text: String) -> String {
+    if !text.contains('\r') {
+        return text;
+    }
+    let mut bytes = text.into_bytes();
+    let mut length = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        index += 1;
+        bytes[length] = match byte {
+            b'\r' => {
+                if bytes.get(index) == Some(&b'\n') {
+                    index += 1;
+                }
+                b'\n'
+            }
+            _ => byte,
+        };
+        length += 1;
+    }
+    bytes.truncate(length);
+    String::from_utf8(bytes).expect("only ASCII bytes are replaced")
+}
+
+/// Removes HTML comments from a text. A comment without an end is removed
+/// up to the end of the text, as MediaWiki does.
+fn strip_html_comments(text: String) -> String {
+    let mut rest = match text.find("<!--") {
+        None => return text,
+        Some(start) => start,
+    };
+    let mut output = String::with_capacity(text.len());
+    output.push_str(&text[..rest]);
+    loop {
+        // The end is searched for after `<!--`, so `<!-->` doesn't end
+        // the comment.
+        match text[rest + 4..].find("-->") {
+            None => return output,
+            Some(end) => rest += 4 + end + 3,
+        }
+        match text[rest..].find("<!--") {
+            None => {
+                output.push_str(&text[rest..]);
+                return output;
+            }
+            Some(start) => {
+                output.push_str(&text[rest..rest + start]);
+                rest += start;
+            }
+        }
+    }
+}
+
+/// Applies the function set with [`ParserBuilder::text_transform`] if any
+/// to the value of a field.
+fn transform<R: BufRead, N>(
+    parser: &mut Parser<R, N>,
+    field: Field,
+    value: String,
+) -> String {
+    match &mut parser.options.text_transform {
+        None => value,
+        Some(transform) => (transform.0)(field, value),
+    }
+}
+
+/// Removes leading and trailing whitespace from a format or model and makes
+/// it lowercase.
+fn normalize_model(value: &str) -> String {
+    value.trim().to_lowercase()
+}
+
+/// Returns whether a model or format is in the allowlist if one is set.
+fn is_allowed(allowed: &Option<Vec<String>>, value: &str) -> bool {
+    match allowed {
+        None => true,
+        Some(allowed) => allowed.iter().any(|allowed| allowed == value),
+    }
+}
+
+/// Creates a parser for a stream in which namespaces are represented as
+/// [`NamespaceId`]. Equivalent to `parse_with_namespace` with the second
+/// generic argument set to `NamespaceId` (`parse_with_namespace::<_, NamespaceId>`).
+///
+/// The stream is parsed as an XML dump exported from MediaWiki. The parser is
+/// an iterator over the pages in the dump.
+pub fn parse<R: BufRead>(source: R) -> Parser<R, NamespaceId> {
+    parse_with_namespace(source)
+}
+
+/// Creates a parser for a stream. Allows you to select a type for the namespace.
+///
+/// The stream is parsed as an XML dump exported from MediaWiki. The parser is
+/// an iterator over the pages in the dump.
+pub fn parse_with_namespace<R: BufRead, N: FromNamespaceId>(
+    source: R,
+) -> Parser<R, N> {
+    ParserBuilder::new().parse_with_namespace(source)
+}
+
+/// Creates a parser for the metadata of the pages in a stream, in which
+/// namespaces are represented as [`NamespaceId`].
+///
+/// The `revision` element of each page is skipped, so the parser only yields
+/// the id, namespace, title and redirect target of each page.
+pub fn parse_meta<R: BufRead>(source: R) -> MetaParser<R, NamespaceId> {
+    parse_meta_with_namespace(source)
+}
+
+/// Creates a parser for the metadata of the pages in a stream.
+/// Allows you to select a type for the namespace.
+pub fn parse_meta_with_namespace<R: BufRead, N: FromNamespaceId>(
+    source: R,
+) -> MetaParser<R, N> {
+    ParserBuilder::new().parse_meta_with_namespace(source)
+}
+
+/// Parser for a gzip compressed stream, as created by [`parse_gz`].
+#[cfg(feature = "flate2")]
+pub type GzParser<R> =
+    Parser<std::io::BufReader<flate2::bufread::MultiGzDecoder<R>>, NamespaceId>;
+
+/**
+Opens a gzip compressed file and creates a parser for it, in which namespaces
+are represented as [`NamespaceId`].
+
+Only available with the `flate2` feature.
+
+The file may consist of several concatenated gzip members, as produced by
+some mirrors. A truncated or corrupt stream results in an [`Error::Io`]
+wrapping the error from the decoder.
+*/
+#[cfg(feature = "flate2")]
+pub fn parse_gz(
+    path: impl AsRef<std::path::Path>,
+) -> std::io::Result<GzParser<std::io::BufReader<std::fs::File>>> {
+    let file = std::fs::File::open(path)?;
+    Ok(parse_gz_reader(std::io::BufReader::new(file)))
+}
+
+/// Creates a parser for a gzip compressed stream, in which namespaces are
+/// represented as [`NamespaceId`].
+///
+/// Only available with the `flate2` feature.
+#[cfg(feature = "flate2")]
+pub fn parse_gz_reader<R: BufRead>(source: R) -> GzParser<R> {
+    parse(std::io::BufReader::new(
+        flate2::bufread::MultiGzDecoder::new(source),
+    ))
+}
+
+impl ParserBuilder {
+    /// Creates a builder with the default options.
+    pub fn new() -> Self {
+        Self {
+            allowed_formats: None,
+            allowed_models: None,
+            cancellation: None,
+            capture_site_info: false,
+            capture_threads: false,
+            capture_unknown_page_fields: false,
+            check_xmlns: true,
+            collect_warnings: false,
+            decode_entities: true,
+            default_namespace: None,
+            detect_duplicate_titles: false,
+            #[cfg(feature = "encoding_rs")]
+            encoding: None,
+            filter: None,
+            expand_empty_elements: true,
+            lenient_text: false,
+            max_namespace_buffer: None,
+            max_revisions_per_page: None,
+            multiple_roots: false,
+            namespace_range: None,
+            normalize_model: false,
+            normalize_newlines: false,
+            on_namespace_change: None,
+            on_unknown_element: None,
+            on_unsupported: None,
+            page_range: None,
+            partial_page_errors: false,
+            raw_namespace: false,
+            record_empty_text_element: false,
+            recover_from_errors: false,
+            resolve_text: None,
+            schema_versions: Some(KNOWN_SCHEMA_VERSIONS.to_vec()),
+            skip_unmatched: false,
+            skip_unsupported: false,
+            strip_html_comments: false,
+            strip_text_bom: false,
+            text_contains: None,
+            text_hint: 0,
+            #[cfg(feature = "sha1")]
+            text_sha1: false,
+            text_spans: false,
+            text_transform: None,
+            title_key: None,
+            xmlns: None,
+        }
+    }
+
+    /// Restricts the versions of the export schema that are accepted.
+    ///
+    /// Each version is a pair of major and minor version, so `(0, 10)`
+    /// accepts elements in the XML namespace
+    /// `http://www.mediawiki.org/xml/export-0.10/`. Elements in the namespace
+    /// of any other version are treated as if they were unknown elements,
+    /// which means a root element of another version is a format error.
+    ///
+    /// By default all versions from 0.3 to 0.11 are accepted.
+    pub fn accept_schema_versions(mut self, versions: &[(u32, u32)]) -> Self {
+        self.schema_versions = Some(versions.to_vec());
+        self
+    }
+
+    /// Accepts any version of the export schema, including versions newer
+    /// than those known when this crate was published.
+    pub fn accept_any_schema(mut self) -> Self {
+        self.schema_versions = None;
+        self
+    }
+
+    /**
+    Accepts elements in the given XML namespace as elements of the export
+    schema, in addition to the namespaces of the accepted schema versions.
+
+    Some forks of MediaWiki export dumps in a namespace of their own, which
+    by default are rejected since their root element is not recognized.
+    Elements in the namespace are recognized by their local names like
+    elements of the export schema.
+
+    Not set by default.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="https://wiki.example/xml/export-1.0/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .xmlns("https://wiki.example/xml/export-1.0/")
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.title, "alpha");
+    ```
+    */
+    pub fn xmlns(mut self, namespace: &str) -> Self {
+        self.xmlns = Some(namespace.as_bytes().to_vec());
+        self
+    }
+
+    /**
+    Restricts the content models of revisions that are accepted.
+
+    A revision whose `model` element is not one of the given models causes
+    [`Error::UnexpectedModel`], so that a dump expected to contain only
+    wiki text fails fast if it contains for example a Lua module. The model
+    is compared as it is stored in the `model` field of the page, so after
+    normalization if [`ParserBuilder::normalize_model`] is enabled.
+    Revisions without a `model` element are accepted.
+
+    By default all models are accepted.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page>
+            <ns>8</ns>
+            <title>MediaWiki:Common.css</title>
+            <revision><model>css</model><text/></revision>
+        </page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .allowed_models(&["wikitext"])
+        .parse(dump.as_bytes());
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::UnexpectedModel { .. }))
+    ));
+    ```
+    */
+    pub fn allowed_models(mut self, models: &[&str]) -> Self {
+        self.allowed_models =
+            Some(models.iter().map(|model| model.to_string()).collect());
+        self
+    }
+
+    /**
+    Restricts the formats of revisions that are accepted.
+
+    A revision whose `format` element is not one of the given formats
+    causes [`Error::UnexpectedFormat`]. Works the same way as
+    [`ParserBuilder::allowed_models`].
+
+    By default all formats are accepted.
+    */
+    pub fn allowed_formats(mut self, formats: &[&str]) -> Self {
+        self.allowed_formats =
+            Some(formats.iter().map(|format| format.to_string()).collect());
+        self
+    }
+
+    /**
+    Sets a flag that cancels parsing when set to `true`, for example from
+    another thread.
+
+    The flag is checked before each page, which costs one atomic load per
+    page. Once it is set, the parser returns [`Error::Cancelled`] and then
+    no more pages, so a parser running in the background can be stopped
+    between pages instead of in the middle of reading the stream.
+
+    ```rust
+    use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+        <page><ns>0</ns><title>beta</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .cancellation(cancelled.clone())
+        .parse(dump.as_bytes());
+    assert!(parser.next().unwrap().is_ok());
+    cancelled.store(true, Ordering::Relaxed);
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::Cancelled { .. }))
+    ));
+    assert!(parser.next().is_none());
+    ```
+    */
+    pub fn cancellation(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancellation = Some(flag);
+        self
+    }
+
+    /**
+    Sets whether to parse the `siteinfo` element before the pages into a
+    [`SiteInfo`], available from [`Parser::site_info`].
+
+    The name of the wiki and the names and aliases of its namespaces are
+    parsed, from which [`DynamicNamespaces`] can resolve namespace prefixes
+    of titles. The rest of the element is skipped.
+
+    Disabled by default.
+    */
+    pub fn capture_site_info(mut self, enable: bool) -> Self {
+        self.capture_site_info = enable;
+        self
+    }
+
+    /**
+    Sets whether to parse the threading metadata of pages of the
+    LiquidThreads extension into the `thread` field of the page.
+
+    Dumps of some older wikis contain a `DiscussionThreading` element in
+    each page that is a thread, which is otherwise skipped. The name of the
+    element is matched regardless of case. Pages without the element have
+    no thread.
+
+    Disabled by default.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page>
+            <ns>90</ns>
+            <title>Thread:Talk:Alpha/Beta</title>
+            <revision><text/></revision>
+            <DiscussionThreading>
+                <ThreadSubject>Beta</ThreadSubject>
+                <ThreadID>2</ThreadID>
+            </DiscussionThreading>
+        </page>
+    </mediawiki>"#;
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .capture_threads(true)
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    let thread = page.thread.unwrap();
+    assert_eq!(thread.subject.as_deref(), Some("Beta"));
+    assert_eq!(thread.id, Some(2));
+    ```
+    */
+    pub fn capture_threads(mut self, enable: bool) -> Self {
+        self.capture_threads = enable;
+        self
+    }
+
+    /**
+    Sets whether to keep the content of the child elements of the `page`
+    element that aren't otherwise parsed in the `extra` field of the page.
+
+    Some installations add their own elements to pages, such as `sha1` or
+    `discussionid`, which are otherwise skipped. If enabled, the text
+    content of each such element is kept by the local name of the element,
+    with any markup within it kept as written. Elements in other XML
+    namespaces are still skipped.
+
+    Disabled by default.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page>
+            <ns>0</ns>
+            <title>alpha</title>
+            <discussionid>1</discussionid>
+            <revision><text/></revision>
+        </page>
+    </mediawiki>"#;
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .capture_unknown_page_fields(true)
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.extra["discussionid"], "1");
+    ```
+    */
+    pub fn capture_unknown_page_fields(mut self, enable: bool) -> Self {
+        self.capture_unknown_page_fields = enable;
+        self
+    }
+
+    /**
+    Sets whether to accept markup within the `text` element of a revision.
+
+    Some dumps contain markup that was not escaped within the text, such as
+    `<br>`, which is an error by default. If enabled, such markup is kept in
+    the text as written, so that the rest of the dump can be salvaged.
+    Elements left open, like `<br>`, end at the end tag of the `text`
+    element. Since end tags can't then be required to match start tags,
+    they are no longer checked anywhere in the stream.
+
+    Has no effect on the `text` element if [`ParserBuilder::text_spans`]
+    is enabled. Disabled by default.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text>a<br>b</text></revision></page>
+    </mediawiki>"#;
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .lenient_text(true)
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.text, "a<br>b");
+    ```
+    */
+    pub fn lenient_text(mut self, enable: bool) -> Self {
+        self.lenient_text = enable;
+        self
+    }
+
+    /**
+    Sets a bound on the capacity that the buffer for XML namespace
+    declarations retains between pages.
+
+    The buffer grows to hold the namespace declarations in scope and is
+    never shrunk by default, so a document that declares many namespaces
+    within one page makes the parser retain the peak memory for the rest
+    of the stream. With a bound, the capacity is reduced to the bound, or
+    to the length of the declarations still in scope if that is greater,
+    before each page is parsed.
+
+    This is a knob for the memory retained by long running parsers,
+    not a change to what is parsed. Not set by default.
+    */
+    pub fn max_namespace_buffer(mut self, capacity: usize) -> Self {
+        self.max_namespace_buffer = Some(capacity);
+        self
+    }
+
+    /**
+    Sets the greatest number of revisions of one page that
+    [`Parser::revisions_flat`] returns. Only applies to
+    [`Parser::revisions_flat`].
+
+    Parsing pages with the [`Iterator`] implementation of [`Parser`] and
+    the other page-level APIs ignores this option, since they already return
+    [`Error::NotSupported`] for a page with more than one revision.
+
+    When a page has more revisions, the rest of the page is skipped and
+    [`Error::TooManyRevisions`] is returned instead of the first revision
+    exceeding the limit. Iteration can continue with the next page, so
+    a consumer that collects the revisions of each page can bound the memory
+    used by the few pages with hundreds of thousands of revisions and still
+    process the rest of the dump. Not set by default.
+    */
+    pub fn max_revisions_per_page(mut self, limit: usize) -> Self {
+        self.max_revisions_per_page = Some(limit);
+        self
+    }
+
+    /**
+    Sets whether the stream can contain more than one `mediawiki` element
+    one after another.
+
+    This allows parsing several dumps concatenated into one stream, such as
+    the parts of a dump that Wikimedia splits into several files, each
+    of which has its own `mediawiki` element. Elements other than `page`
+    elements, such as the `siteinfo` element of each part, are skipped as
+    usual. See [`ChainedReader`] for concatenating streams.
+
+    If enabled, the parser reads until the end of the stream instead of
+    stopping after the end tag of the first `mediawiki` element.
+
+    Disabled by default.
+    */
+    pub fn multiple_roots(mut self, enable: bool) -> Self {
+        self.multiple_roots = enable;
+        self
+    }
+
+    /**
+    Sets a filter combining predicates on namespaces, redirects, titles and
+    models, so that only pages passing it are returned. See [`Filter`].
+
+    Pages that don't pass are skipped as soon as they are known not to,
+    without parsing the rest of the page.
+    */
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /**
+    Sets a range of indexes of pages outside of which pages are skipped,
+    counted from zero in the order of the `page` elements in the stream.
+
+    This divides a dump into contiguous shards by the number of pages
+    without an index of the dump, so that each of several workers can parse
+    one shard, such as `0..1000`, `1000..2000` and so on. The pages before
+    the range are skipped as XML events without being parsed and their text
+    isn't allocated, and parsing stops after the last page of the range, so
+    the rest of the stream isn't read.
+
+    Every `page` element is counted, including pages skipped because of
+    other options such as [`ParserBuilder::namespace_range`] or
+    [`ParserBuilder::filter`].
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+        <page><ns>0</ns><title>beta</title><revision><text/></revision></page>
+        <page><ns>0</ns><title>gamma</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let titles = parse_mediawiki_dump::ParserBuilder::new()
+        .page_range(1..2)
+        .parse(dump.as_bytes())
+        .map(|page| page.map(|page| page.title))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(titles, ["beta"]);
+    ```
+    */
+    pub fn page_range(mut self, range: Range<usize>) -> Self {
+        self.page_range = Some(range);
+        self
+    }
+
+    /**
+    Sets a range of namespaces outside of which pages are skipped.
+
+    This relies on the pages of the dump being sorted by namespace, as they
+    are in the dumps from Wikimedia: pages in a namespace before the range
+    are skipped, and parsing stops at the first page in a namespace after
+    the range, so the rest of a huge dump isn't read. If the dump isn't
+    sorted, pages after that page are missed.
+
+    A page is skipped as soon as its `ns` element has been parsed, without
+    parsing the rest of the page.
+
+    ```rust
+    use parse_mediawiki_dump::NamespaceId;
+
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+        <page><ns>1</ns><title>Talk:beta</title><revision><text/></revision></page>
+        <page><ns>2</ns><title>User:gamma</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let titles = parse_mediawiki_dump::ParserBuilder::new()
+        .namespace_range(NamespaceId(1)..=NamespaceId(1))
+        .parse(dump.as_bytes())
+        .map(|page| page.map(|page| page.title))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(titles, ["Talk:beta"]);
+    ```
+    */
+    pub fn namespace_range(
+        mut self,
+        range: RangeInclusive<NamespaceId>,
+    ) -> Self {
+        self.namespace_range = Some(range);
+        self
+    }
+
+    /**
+    Sets whether to normalize the format and model of each revision.
+
+    If enabled, leading and trailing whitespace is removed from the `format`
+    and `model` fields and they are made lowercase, so that they can be
+    compared with values such as `wikitext` and `text/x-wiki` even if
+    an extension writes them differently. The values as written in the dump
+    are kept in the `raw_format` and `raw_model` fields.
+
+    Disabled by default.
+    */
+    pub fn normalize_model(mut self, enable: bool) -> Self {
+        self.normalize_model = enable;
+        self
+    }
+
+    /**
+    Sets whether to replace the line endings CRLF and CR in the text of
+    each page with LF.
+
+    MediaWiki saves texts with LF line endings, but some dumps made by
+    other tools contain CRLF. Normalizing them while parsing allows
+    processing the texts consistently without another pass over them.
+    The SHA-1 computed with [`ParserBuilder::text_sha1`] is still that of
+    the text as written in the dump.
+
+    Disabled by default, so that the text is kept as written.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text>beta&#13;&#10;gamma&#13;delta</text></revision></page>
+    </mediawiki>"#;
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .normalize_newlines(true)
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.text, "beta\ngamma\ndelta");
+    ```
+    */
+    pub fn normalize_newlines(mut self, enable: bool) -> Self {
+        self.normalize_newlines = enable;
+        self
+    }
+
+    /**
+    Sets a function to call when a page is returned in a namespace other
+    than that of the previous page returned, with the namespace of the
+    previous page, if any, and the new namespace.
+
+    The pages of the dumps from Wikimedia are sorted by namespace, so the
+    function is called once for each namespace, before the first page in
+    it is returned. This makes it possible to log the progress through a
+    dump.
+
+    ```rust
+    let parser = parse_mediawiki_dump::ParserBuilder::new()
+        .on_namespace_change(|_, namespace| {
+            eprintln!("Now processing namespace {}", namespace.into_inner())
+        })
+        .parse(std::io::stdin().lock());
+    ```
+    */
+    pub fn on_namespace_change(
+        mut self,
+        callback: impl FnMut(Option<NamespaceId>, NamespaceId) + Send + 'static,
+    ) -> Self {
+        self.on_namespace_change = Some(Callback(Box::new(callback)));
+        self
+    }
+
+    /**
+    Sets a function to call with each element in a `page` or `revision`
+    element that the parser skips because it doesn't know it, such as an
+    element added by an extension.
+
+    The function receives the name of the element as written, with any
+    prefix, the attributes of its start tag as pairs of name and raw value,
+    and a [`DecoderCtx`] to unescape and decode the values the same way as
+    the parser decodes the fields of pages. The element is then skipped as
+    usual. Elements captured with
+    [`ParserBuilder::capture_unknown_page_fields`] are not passed to the
+    function.
+
+    ```rust
+    use std::sync::{Arc, Mutex};
+
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page>
+            <ns>0</ns>
+            <title>alpha</title>
+            <quality level="b&amp;c" />
+            <revision><text/></revision>
+        </page>
+    </mediawiki>"#;
+    let levels = Arc::new(Mutex::new(vec![]));
+    let parser = parse_mediawiki_dump::ParserBuilder::new()
+        .on_unknown_element({
+            let levels = levels.clone();
+            move |name, attributes, decoder| {
+                for (key, value) in attributes {
+                    if name == "quality" && *key == b"level" {
+                        levels.lock().unwrap().push(decoder.unescape(value).unwrap());
+                    }
+                }
+            }
+        })
+        .parse(dump.as_bytes());
+    parser.for_each(|page| drop(page.unwrap()));
+    assert_eq!(*levels.lock().unwrap(), ["b&c"]);
+    ```
+    */
+    pub fn on_unknown_element(
+        mut self,
+        callback: impl FnMut(&str, &[(&[u8], &[u8])], &DecoderCtx) + Send + 'static,
+    ) -> Self {
+        self.on_unknown_element = Some(Callback(Box::new(callback)));
+        self
+    }
+
+    /**
+    Sets a function to call when a `page` element containing more than one
+    `revision` element is encountered, before the error
+    [`Error::NotSupported`] is returned or the page is skipped.
+
+    The function receives the fields of the page parsed so far and the
+    position in the stream. This makes it possible to log a helpful message,
+    since such pages usually mean that a dump with the full history of each
+    page was given to the parser by mistake.
+
+    ```rust
+    let parser = parse_mediawiki_dump::ParserBuilder::new()
+        .on_unsupported(|page, position| {
+            eprintln!(
+                "Page {:?} at position {} has more than one revision. \
+                 Is this a dump with full history?",
+                page.title, position,
+            )
+        })
+        .parse(std::io::stdin().lock());
+    ```
+    */
+    pub fn on_unsupported(
+        mut self,
+        callback: impl FnMut(&PartialPage, usize) + Send + 'static,
+    ) -> Self {
+        self.on_unsupported = Some(Callback(Box::new(callback)));
+        self
+    }
+
+    /**
+    Sets whether to keep the text content of each `ns` element as written
+    in the dump in the `raw_namespace` field of the page.
+
+    The namespace id is parsed after removing leading and trailing
+    whitespace, so the parsed id doesn't tell how it was written. The raw
+    text is useful for reproducing a dump byte for byte and for diagnosing
+    unusual dumps.
+
+    Disabled by default, to avoid an extra allocation for each page.
+    */
+    pub fn raw_namespace(mut self, enable: bool) -> Self {
+        self.raw_namespace = enable;
+        self
+    }
+
+    /**
+    Sets a function to fetch the text of a revision that is stored outside
+    the dump.
+
+    The function is called with the `location` attribute of each `text`
+    element that has one and no content, and its result is used as the
+    text. An error from the function is returned as [`Error::Io`]. Has no
+    effect if [`ParserBuilder::text_spans`] is enabled.
+
+    ```rust
+    let parser = parse_mediawiki_dump::ParserBuilder::new()
+        .resolve_text(|location| {
+            std::fs::read_to_string(location.trim_start_matches("file://"))
+        })
+        .parse(std::io::stdin().lock());
+    ```
+    */
+    pub fn resolve_text(
+        mut self,
+        resolver: impl FnMut(&str) -> std::io::Result<String> + Send + 'static,
+    ) -> Self {
+        self.resolve_text = Some(Callback(Box::new(resolver)));
+        self
+    }
+
+    /**
+    Sets whether the parser keeps parsing after returning an error instead
+    of ending.
+
+    By default the parser returns `None` after an error, except after
+    [`Error::TooManyRevisions`]. If enabled, the parser skips from where
+    the error occurred to the start of the next page. This works for errors
+    that concern a single page, such as [`Error::Namespace`], but after
+    an error in the XML itself the following pages may be missed or give
+    more errors.
+
+    Disabled by default.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>zero</ns><title>alpha</title><revision><text/></revision></page>
+        <page><ns>0</ns><title>beta</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .recover_from_errors(true)
+        .parse(dump.as_bytes());
+    assert!(parser.next().unwrap().is_err());
+    assert_eq!(parser.next().unwrap().unwrap().title, "beta");
+    ```
+    */
+    pub fn recover_from_errors(mut self, enable: bool) -> Self {
+        self.recover_from_errors = enable;
+        self
+    }
+
+    /**
+    Sets a byte string that the text of a revision must contain, to extract
+    the pages mentioning something.
+
+    The text is searched as it is read, after entities are unescaped, and
+    the search stops once the string is found. The text of a page that
+    doesn't contain the string is discarded, so the page is returned with
+    empty text, or skipped if [`ParserBuilder::skip_unmatched`] is enabled.
+    Such texts are not passed to [`ParserBuilder::resolve_text`]. Has no
+    effect if [`ParserBuilder::text_spans`] is enabled.
+
+    Not set by default.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text>beta</text></revision></page>
+        <page><ns>0</ns><title>gamma</title><revision><text>delta</text></revision></page>
+    </mediawiki>"#;
+    let titles = parse_mediawiki_dump::ParserBuilder::new()
+        .text_contains(b"delta")
+        .skip_unmatched(true)
+        .parse(dump.as_bytes())
+        .map(|page| page.map(|page| page.title))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(titles, ["gamma"]);
+    ```
+    */
+    pub fn text_contains(mut self, needle: &[u8]) -> Self {
+        self.text_contains = Some(needle.to_vec());
+        self
+    }
+
+    /// Skips pages whose text doesn't contain the string set with
+    /// [`ParserBuilder::text_contains`] instead of returning them with empty
+    /// text.
+    ///
+    /// Disabled by default.
+    pub fn skip_unmatched(mut self, enable: bool) -> Self {
+        self.skip_unmatched = enable;
+        self
+    }
+
+    /// Skips pages containing more than one `revision` element instead of
+    /// returning [`Error::NotSupported`].
+    ///
+    /// Disabled by default.
+    pub fn skip_unsupported(mut self, enable: bool) -> Self {
+        self.skip_unsupported = enable;
+        self
+    }
+
+    /// Wraps errors that occur within a `page` element in [`Error::Page`],
+    /// together with the title and namespace of the page if they were parsed
+    /// before the error.
+    ///
+    /// This makes it possible to tell which page is malformed in a large dump.
+    /// Disabled by default.
+    pub fn partial_page_errors(mut self, enable: bool) -> Self {
+        self.partial_page_errors = enable;
+        self
+    }
+
+    /**
+    Sets whether to remove HTML comments, `<!--` up to and including `-->`,
+    from the text of each page.
+
+    Since the text is character data, comments in wiki text are escaped in
+    the dump and are part of the text, and many consumers remove them
+    before processing the text. A comment without an end is removed up to
+    the end of the text, as MediaWiki does. This is done after the text is
+    read, so the text searched with [`ParserBuilder::text_contains`] and
+    the SHA-1 computed with [`ParserBuilder::text_sha1`] still include
+    the comments.
+
+    Disabled by default.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page>
+            <ns>0</ns>
+            <title>alpha</title>
+            <revision><text>beta&lt;!-- gamma --&gt; delta</text></revision>
+        </page>
+    </mediawiki>"#;
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .strip_html_comments(true)
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.text, "beta delta");
+    ```
+    */
+    pub fn strip_html_comments(mut self, enable: bool) -> Self {
+        self.strip_html_comments = enable;
+        self
+    }
+
+    /// Sets whether to remove a byte order mark (U+FEFF) from the start of
+    /// the text and title of each page.
+    ///
+    /// Such a character is invisible but can confuse tools processing
+    /// the text. It is only found in dumps that have been mangled by some
+    /// tool, so it is kept unless this is enabled. The SHA-1 computed with
+    /// [`ParserBuilder::text_sha1`] is still that of the text as written
+    /// in the dump.
+    ///
+    /// Disabled by default.
+    pub fn strip_text_bom(mut self, enable: bool) -> Self {
+        self.strip_text_bom = enable;
+        self
+    }
+
+    /// Sets the number of bytes to reserve for the text of each page before
+    /// reading it.
+    ///
+    /// Setting this to the typical size of the texts in a dump avoids
+    /// growing the `text` field of each page repeatedly while its text is
+    /// read. Texts longer than the hint still grow as needed. Texts of empty
+    /// `text` elements are not allocated. If the `text` element declares
+    /// the length of the text in its `bytes` attribute, as in recent dumps,
+    /// that length is reserved instead if it is greater, up to 64 MiB.
+    ///
+    /// 0 by default.
+    pub fn text_hint(mut self, capacity: usize) -> Self {
+        self.text_hint = capacity;
+        self
+    }
+
+    /// Computes the SHA-1 of the text of each page while parsing it and
+    /// stores it in the `text_sha1` field of the page.
+    ///
+    /// The text is hashed chunk by chunk as it is read, so no second pass
+    /// over the text is needed. This is useful for verifying the text
+    /// against the `sha1` field and for dumps that don't contain the `sha1`
+    /// element. Has no effect if [`ParserBuilder::text_spans`] is enabled.
+    ///
+    /// Only available with the `sha1` feature. Disabled by default.
+    #[cfg(feature = "sha1")]
+    pub fn text_sha1(mut self, enable: bool) -> Self {
+        self.text_sha1 = enable;
+        self
+    }
+
+    /**
+    Sets whether to record in the `text_was_empty_element` field of each
+    page whether its `text` element is written as `<text/>`, so that a page
+    can be written back in the same form.
+
+    The two forms are otherwise parsed the same way, both giving an empty
+    text. Enabling this disables [`ParserBuilder::expand_empty_elements`],
+    since the forms can't be told apart once empty elements are expanded.
+
+    Disabled by default.
+    */
+    pub fn record_empty_text_element(mut self, enable: bool) -> Self {
+        self.record_empty_text_element = enable;
+        self
+    }
+
+    /// Records the location of the text of each page instead of reading it.
+    ///
+    /// If enabled, the `text` field of each page is empty and the
+    /// `text_span` field contains the byte offsets of the start and end of
+    /// the content of the `text` element in the stream, so the text can be
+    /// read later from a copy of the stream, for example a memory-mapped
+    /// decompressed dump. The content between the offsets is the raw XML,
+    /// in which entities have not been unescaped.
+    ///
+    /// The offsets count the bytes read from the source of the parser,
+    /// starting from zero. For a `text` element with no content, including
+    /// the empty-element form `<text/>`, both offsets are the same.
+    ///
+    /// Disabled by default.
+    pub fn text_spans(mut self, enable: bool) -> Self {
+        self.text_spans = enable;
+        self
+    }
+
+    /**
+    Sets a function to transform the value of each field of a page as it is
+    parsed, so that it can be normalized without another pass over the
+    pages.
+
+    The function receives the field, identified by [`Field`], and its value
+    after unescaping, and returns the value to keep. The SHA-1 computed with
+    [`ParserBuilder::text_sha1`] is that of the text before the function is
+    applied, and the function is not applied to the text if
+    [`ParserBuilder::text_spans`] is enabled.
+
+    ```rust
+    use parse_mediawiki_dump::Field;
+
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text>beta</text></revision></page>
+    </mediawiki>"#;
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .text_transform(|field, value| match field {
+            Field::Title => value.to_uppercase(),
+            _ => value,
+        })
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!((page.title.as_str(), page.text.as_str()), ("ALPHA", "beta"));
+    ```
+    */
+    pub fn text_transform(
+        mut self,
+        transform: impl FnMut(Field, String) -> String + Send + 'static,
+    ) -> Self {
+        self.text_transform = Some(Callback(Box::new(transform)));
+        self
+    }
+
+    /**
+    Sets a builder of hashers to compute the `title_key` field of each page
+    with [`title_key`] while parsing.
+
+    This saves a pass over the titles for building a graph of pages keyed by
+    a hash of their names, since the key of a link target can be computed
+    with the same function. For keys that are the same across runs, use
+    a hasher without random state, such as
+    `BuildHasherDefault<DefaultHasher>`.
+
+    ```rust
+    use parse_mediawiki_dump::{title_key, NamespaceId};
+    use std::{collections::hash_map::DefaultHasher, hash::BuildHasherDefault};
+
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let hasher = BuildHasherDefault::<DefaultHasher>::default();
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .title_key_hasher(hasher.clone())
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.title_key, Some(title_key(&hasher, NamespaceId(0), "alpha")));
+    ```
+    */
+    pub fn title_key_hasher(
+        mut self,
+        hasher: impl BuildHasher + Send + 'static,
+    ) -> Self {
+        self.title_key = Some(Callback(Box::new(move |namespace, title| {
+            title_key(&hasher, namespace, title)
+        })));
+        self
+    }
+
+    /**
+    Sets whether to record problems that the parser works around instead of
+    returning an error, to be read with [`Parser::warnings`].
+
+    See [`Warning`] for the problems that are recorded. This gives a way to
+    notice when options such as [`ParserBuilder::default_namespace`] or
+    [`ParserBuilder::skip_unsupported`] take effect, without stopping
+    the parser.
+
+    Disabled by default.
+
+    ```rust
+    use parse_mediawiki_dump::{NamespaceId, Warning};
+
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><title>alpha</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .default_namespace(NamespaceId(0))
+        .collect_warnings(true)
+        .parse(dump.as_bytes());
+    assert!(parser.next().unwrap().is_ok());
+    assert!(matches!(
+        parser.warnings(),
+        [Warning::DefaultNamespace { .. }]
+    ));
+    ```
+    */
+    pub fn collect_warnings(mut self, enable: bool) -> Self {
+        self.collect_warnings = enable;
+        self
+    }
+
+    /**
+    Sets whether to check that no two pages have the same namespace and
+    title, which happens when dumps with overlapping pages are concatenated.
+
+    A repeated page is returned as [`Error::DuplicateTitle`], or, if
+    [`ParserBuilder::collect_warnings`] is enabled, returned as usual with
+    [`Warning::DuplicateTitle`] recorded. The namespace and title of every
+    page returned is kept in a set until the parser is reset, which for
+    a dump of all the pages of the English Wikipedia takes several hundred
+    megabytes.
+
+    Disabled by default.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+        <page><ns>1</ns><title>alpha</title><revision><text/></revision></page>
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .detect_duplicate_titles(true)
+        .parse(dump.as_bytes());
+    assert!(parser.next().unwrap().is_ok());
+    assert!(parser.next().unwrap().is_ok());
+    assert!(matches!(
+        parser.next().unwrap(),
+        Err(parse_mediawiki_dump::Error::DuplicateTitle { .. })
+    ));
+    ```
+    */
+    pub fn detect_duplicate_titles(mut self, enable: bool) -> Self {
+        self.detect_duplicate_titles = enable;
+        self
+    }
+
+    /**
+    Sets whether to check that elements are in the XML namespace of the export
+    schema.
+
+    If disabled, namespace declarations are not resolved and elements are
+    recognized purely by their local names, which makes parsing faster
+    because less work is done for every element. On a dump of pages with
+    short texts, parsing took about a third less time. The trade-off is that
+    elements from other XML namespaces with the same local names as elements
+    of the export schema are mistaken for them, and the version of the schema
+    is not checked, so [`ParserBuilder::accept_schema_versions`] has no effect.
+    This is safe for dumps exported from MediaWiki, which use no other
+    namespaces.
+
+    Enabled by default.
+    */
+    pub fn check_xmlns(mut self, enable: bool) -> Self {
+        self.check_xmlns = enable;
+        self
+    }
+
+    /**
+    Sets whether to replace entity and character references, such as `&amp;`
+    and `&#10;`, with the characters they stand for in the strings of the
+    page.
+
+    If disabled, the title, text and every other string are kept as they
+    are written in the dump, so that they can be written back unchanged.
+    CDATA sections are never escaped, so they are kept as they are either
+    way. The SHA-1 computed with [`ParserBuilder::text_sha1`] and the search
+    with [`ParserBuilder::text_contains`] are then of the escaped text, which
+    doesn't match the SHA-1 given in the dump if the text contains any
+    reference.
+
+    Enabled by default.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page>
+            <ns>0</ns>
+            <title>A&amp;B</title>
+            <revision><text>&lt;br&gt;</text></revision>
+        </page>
+    </mediawiki>"#;
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .decode_entities(false)
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.title, "A&amp;B");
+    assert_eq!(page.text, "&lt;br&gt;");
+    ```
+    */
+    pub fn decode_entities(mut self, enable: bool) -> Self {
+        self.decode_entities = enable;
+        self
+    }
+
+    /**
+    Sets the encoding of the stream by its label, such as `windows-1252`
+    or `iso-8859-1`, as recognized by web browsers. Only available with the
+    `encoding_rs` feature.
+
+    By default the stream is decoded as UTF-8, whatever the XML
+    declaration says. Dumps exported by MediaWiki are always UTF-8, but
+    some older exports use an encoding such as Latin-1, in which the markup
+    is ASCII but text and attribute values are not valid UTF-8. With an
+    encoding set, they are decoded from it instead, so the fields of the
+    pages are UTF-8 as usual. The encoding must be compatible with ASCII.
+
+    ```rust
+    let dump = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>
+    <mediawiki xmlns=\"http://www.mediawiki.org/xml/export-0.10/\">
+        <page><ns>0</ns><title>Caf\xe9</title><revision><text>na\xefve</text></revision></page>
+    </mediawiki>";
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .encoding("iso-8859-1")
+        .parse(&dump[..])
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.title, "Café");
+    assert_eq!(page.text, "naïve");
+    ```
+
+    # Panics
+
+    Panics if the label is not the label of an encoding, or if the encoding
+    is not compatible with ASCII, such as UTF-16.
+    */
+    #[cfg(feature = "encoding_rs")]
+    pub fn encoding(mut self, label: &str) -> Self {
+        let encoding = encoding_rs::Encoding::for_label(label.as_bytes())
+            .unwrap_or_else(|| panic!("unknown encoding {:?}", label));
+        assert!(
+            encoding.is_ascii_compatible(),
+            "encoding {} is not compatible with ASCII",
+            encoding.name()
+        );
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /**
+    Sets the namespace of pages without an `ns` element.
+
+    The `ns` element is mandatory in the schema, so by default a page
+    without one is an error. Some fragmentary exports and hand-written dumps
+    omit it, and with this option such pages are treated as if they were in
+    the given namespace, usually `NamespaceId(0)`. Returns
+    [`Error::Namespace`] if the namespace cannot be converted to the
+    namespace type of the parser.
+
+    Not set by default.
+    */
+    pub fn default_namespace(mut self, namespace: NamespaceId) -> Self {
+        self.default_namespace = Some(namespace);
+        self
+    }
+
+    /**
+    Sets whether the XML reader expands empty elements such as `<redirect/>`
+    into a start tag and an end tag, as if they were written
+    `<redirect></redirect>`.
+
+    The two forms are parsed the same way either way. If disabled, the reader
+    reports empty elements as they are written, which saves reading
+    a synthetic end tag for every empty element, such as the `minor` element
+    of revisions or the `text` element of revisions whose text is deleted.
+
+    Enabled by default.
+    */
+    pub fn expand_empty_elements(mut self, enable: bool) -> Self {
+        self.expand_empty_elements = enable;
+        self
+    }
+
+    /// Creates a parser for a stream in which namespaces are represented as
+    /// [`NamespaceId`].
+    pub fn parse<R: BufRead>(self, source: R) -> Parser<R, NamespaceId> {
+        self.parse_with_namespace(source)
+    }
+
+    /// Creates a parser for a stream. Allows you to select a type for
+    /// the namespace.
+    pub fn parse_with_namespace<R: BufRead, N: FromNamespaceId>(
+        self,
+        source: R,
+    ) -> Parser<R, N> {
+        Parser {
+            buffer: vec![],
+            current_page: None,
+            ended: false,
+            last_namespace: None,
+            namespace_buffer: vec![],
+            page_index: 0,
+            page_start: 0,
+            page_start_read: false,
+            past_range: false,
+            reader: new_reader(source, &self),
+            seen_titles: HashSet::new(),
+            options: self,
+            recovering: false,
+            shared_position: None,
+            site_info: None,
+            started: false,
+            stats: ParserStats::default(),
+            text_buffer: String::new(),
+            text_buffer_length: None,
+            warnings: vec![],
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates a parser for the metadata of the pages in a stream, in which
+    /// namespaces are represented as [`NamespaceId`].
+    pub fn parse_meta<R: BufRead>(
+        self,
+        source: R,
+    ) -> MetaParser<R, NamespaceId> {
+        self.parse_meta_with_namespace(source)
+    }
+
+    /// Creates a parser for the metadata of the pages in a stream.
+    /// Allows you to select a type for the namespace.
+    pub fn parse_meta_with_namespace<R: BufRead, N: FromNamespaceId>(
+        self,
+        source: R,
+    ) -> MetaParser<R, N> {
+        MetaParser(self.parse_with_namespace(source))
+    }
+
+    fn match_namespace(&self, namespace: Option<&[u8]>) -> bool {
+        if let (Some(namespace), Some(xmlns)) = (namespace, &self.xmlns) {
+            if namespace == xmlns.as_slice() {
+                return true;
+            }
+        }
+        match namespace.and_then(schema_version) {
+            None => false,
+            Some(version) => match &self.schema_versions {
+                None => true,
+                Some(versions) => versions.contains(&version),
+            },
+        }
+    }
+}
+
+fn new_reader<R: BufRead>(source: R, options: &ParserBuilder) -> Reader<R> {
+    let mut reader = Reader::from_reader(source);
+    reader.expand_empty_elements(
+        options.expand_empty_elements && !options.record_empty_text_element,
+    );
+    reader.check_end_names(!options.lenient_text);
+    reader
+}
+
+impl<R: BufRead, N> Parser<R, N> {
+    /**
+    Creates a parser for another stream with the same options, reusing
+    the buffers of this parser.
+
+    This avoids allocating new buffers when parsing many small dumps in
+    a loop. The new parser starts at the beginning of the new stream.
+    The stream can be of a different type than the stream of this parser;
+    use [`Parser::reset_in_place`] to keep the same type. Warnings and stats
+    recorded for the old stream are discarded.
+    */
+    pub fn reset<S: BufRead>(self, source: S) -> Parser<S, N> {
+        Parser {
+            buffer: self.buffer,
+            current_page: None,
+            ended: false,
+            last_namespace: None,
+            namespace_buffer: self.namespace_buffer,
+            page_index: 0,
+            page_start: 0,
+            page_start_read: false,
+            past_range: false,
+            reader: new_reader(source, &self.options),
+            options: self.options,
+            seen_titles: HashSet::new(),
+            recovering: false,
+            shared_position: self.shared_position,
+            site_info: None,
+            started: false,
+            stats: ParserStats::default(),
+            text_buffer: String::new(),
+            text_buffer_length: None,
+            warnings: vec![],
+            phantom: PhantomData,
+        }
+    }
+
+    /// Replaces the stream of this parser with another stream of the same
+    /// type, reusing the buffers of this parser. Parsing continues from
+    /// the beginning of the new stream. Warnings and stats recorded for
+    /// the old stream are discarded.
+    pub fn reset_in_place(&mut self, source: R) {
+        self.buffer.clear();
+        self.current_page = None;
+        self.ended = false;
+        self.last_namespace = None;
+        self.namespace_buffer.clear();
+        self.page_index = 0;
+        self.page_start_read = false;
+        self.past_range = false;
+        self.reader = new_reader(source, &self.options);
+        self.recovering = false;
+        self.seen_titles.clear();
+        self.site_info = None;
+        self.started = false;
+        self.stats = ParserStats::default();
+        self.warnings.clear();
+        update_shared_position(self);
+    }
+
+    /**
+    Returns the stream of the parser.
+
+    The parser reads from the stream only as far as it has parsed, so
+    the rest of the stream can be read after parsing part of it. To keep
+    using a stream after parsing, pass a mutable reference to it to
+    [`parse`] and get the reference back with this method.
+
+    ```rust
+    use std::io::BufRead;
+
+    let mut source = &br#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+        <page><ns>0</ns><title>beta</title><revision><text/></revision></page>
+    </mediawiki>"#[..];
+    let mut parser = parse_mediawiki_dump::parse(&mut source);
+    assert_eq!(parser.next().unwrap().unwrap().title, "alpha");
+    let source = parser.into_inner();
+    assert!(source.lines().next().unwrap().unwrap().trim().is_empty());
+    ```
+    */
+    pub fn into_inner(self) -> R {
+        self.reader.into_underlying_reader()
+    }
+
+    /**
+    Returns a counter that the parser keeps updated with its position in
+    the stream, as the number of bytes read.
+
+    The counter is updated before and after each page, so another thread can
+    read it without locking to show the progress of a parser running in
+    the background. All calls return the same counter. The position is
+    stored with [`Ordering::Relaxed`](std::sync::atomic::Ordering::Relaxed),
+    which costs next to nothing compared to parsing a page.
+
+    ```rust
+    use std::sync::atomic::Ordering;
+
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    let position = parser.shared_position();
+    assert_eq!(position.load(Ordering::Relaxed), 0);
+    assert_eq!(parser.by_ref().count(), 1);
+    assert_eq!(position.load(Ordering::Relaxed), dump.len());
+    ```
+    */
+    pub fn shared_position(&mut self) -> Arc<AtomicUsize> {
+        let position = self.reader.buffer_position();
+        self.shared_position
+            .get_or_insert_with(|| Arc::new(AtomicUsize::new(position)))
+            .clone()
+    }
+}
+
+impl<R: BufRead, N: FromNamespaceId> Parser<R, N> {
+    /**
+    Reads the rest of the stream without parsing the remaining pages,
+    checking that the dump ends cleanly.
+
+    This confirms that a dump was not truncated after stopping early, without
+    the cost of parsing every page. Returns [`Error::UnexpectedEof`] if the
+    stream ends before the end tag of the root element, and
+    [`Error::Format`] if anything but whitespace, comments and processing
+    instructions follows it. Afterwards the parser returns no more pages.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+        <page><ns>0</ns><title>beta</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    assert_eq!(parser.next().unwrap().unwrap().title, "alpha");
+    parser.drain_to_end().unwrap();
+    assert!(parser.next().is_none());
+
+    let truncated = &dump[..dump.find("beta").unwrap()];
+    let mut parser = parse_mediawiki_dump::parse(truncated.as_bytes());
+    assert!(matches!(
+        parser.drain_to_end(),
+        Err(parse_mediawiki_dump::Error::UnexpectedEof(_))
+    ));
+    ```
+    */
+    pub fn drain_to_end(&mut self) -> Result<(), Error> {
+        self.past_range = false;
+        if self.current_page.take().is_some() {
+            skip_element(self)?;
+        }
+        while next_page_start_tag(self)? {
+            skip_element(self)?;
+        }
+        loop {
+            match read_event!(self)?.1 {
+                Event::Eof => return Ok(()),
+                Event::Text(event) => {
+                    if !event.iter().all(u8::is_ascii_whitespace) {
+                        return Err(Error::Format(
+                            self.reader.buffer_position(),
+                        ));
+                    }
                 }
-                PageChildElement::Unknown => skip_element(parser)?,
+                Event::Comment(_) | Event::PI(_) => {}
+                _ => return Err(Error::Format(self.reader.buffer_position())),
+            }
+        }
+    }
+
+    /**
+    Parses the next page like [`Iterator::next`], but appends its text to
+    `buf` instead of allocating a string for it, leaving the `text` field of
+    the page empty.
+
+    The content of `buf` is kept, so it should be cleared before each call
+    unless the texts of several pages are to be concatenated. Since the
+    capacity of `buf` is reused, texts are not allocated once it is large
+    enough. The processing enabled with options of the parser, such as
+    [`ParserBuilder::text_transform`], is applied only to the text of the
+    page. If an error occurs, `buf` may have lost its content.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text>beta</text></revision></page>
+        <page><ns>0</ns><title>gamma</title><revision><text>delta</text></revision></page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    let mut text = String::new();
+    let page = parser.next_text_into(&mut text).unwrap().unwrap();
+    assert_eq!((&*page.title, &*page.text, &*text), ("alpha", "", "beta"));
+    text.clear();
+    parser.next_text_into(&mut text).unwrap().unwrap();
+    assert_eq!(text, "delta");
+    ```
+    */
+    pub fn next_text_into(
+        &mut self,
+        buf: &mut String,
+    ) -> Option<Result<Page<N>, Error>> {
+        self.text_buffer_length = Some(buf.len());
+        self.text_buffer = std::mem::take(buf);
+        let mut result = self.next();
+        self.text_buffer_length = None;
+        let unused = std::mem::take(&mut self.text_buffer);
+        *buf = match &mut result {
+            // The buffer holds the text of the page unless the page has
+            // no text parsed into it, as with `ParserBuilder::text_spans`.
+            Some(Ok(page)) if unused.capacity() == 0 => {
+                std::mem::take(&mut page.text)
+            }
+            _ => unused,
+        };
+        result
+    }
+
+    /**
+    Calls `f` with each page until it returns [`ControlFlow::Break`],
+    returning the first error that occurs while parsing.
+
+    Unlike [`Iterator::try_for_each`], `f` doesn't have to handle parse
+    errors, and can stop early without making up an error. Once it breaks,
+    the rest of the stream is not read.
+
+    ```rust
+    use std::ops::ControlFlow;
+
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+        <page><ns>0</ns><title>beta</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let mut titles = vec![];
+    parse_mediawiki_dump::parse(dump.as_bytes())
+        .for_each_until(|page| {
+            titles.push(page.title);
+            ControlFlow::Break(())
+        })
+        .unwrap();
+    assert_eq!(titles, ["alpha"]);
+    ```
+    */
+    pub fn for_each_until(
+        mut self,
+        mut f: impl FnMut(Page<N>) -> ControlFlow<(), ()>,
+    ) -> Result<(), Error> {
+        for page in &mut self {
+            if let ControlFlow::Break(()) = f(page?) {
+                break;
             }
         }
+        Ok(())
     }
 }
 
-/// Creates a parser for a stream in which namespaces are represented as
-/// [`NamespaceId`]. Equivalent to `parse_with_namespace` with the second
-/// generic argument set to `NamespaceId` (`parse_with_namespace::<_, NamespaceId>`).
-///
-/// The stream is parsed as an XML dump exported from MediaWiki. The parser is
-/// an iterator over the pages in the dump.
-pub fn parse<R: BufRead>(source: R) -> Parser<R, NamespaceId> {
-    parse_with_namespace(source)
+/// Stores the position of the parser in its shared position if there is one.
+fn update_shared_position<R: BufRead, N>(parser: &Parser<R, N>) {
+    if let Some(shared_position) = &parser.shared_position {
+        shared_position
+            .store(parser.reader.buffer_position(), Ordering::Relaxed);
+    }
 }
 
-/// Creates a parser for a stream. Allows you to select a type for the namespace.
-///
-/// The stream is parsed as an XML dump exported from MediaWiki. The parser is
-/// an iterator over the pages in the dump.
-pub fn parse_with_namespace<R: BufRead, N: FromNamespaceId>(
-    source: R,
-) -> Parser<R, N> {
-    let mut reader = Reader::from_reader(source);
-    reader.expand_empty_elements(true);
-    Parser {
-        buffer: vec![],
-        namespace_buffer: vec![],
-        reader,
-        started: false,
-        phantom: PhantomData,
+impl Default for ParserBuilder {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 fn parse_text<R: BufRead, N: FromNamespaceId>(
     parser: &mut Parser<R, N>,
     output: &Option<impl Sized>,
+    empty: bool,
+) -> Result<String, Error> {
+    parse_text_chunks(parser, output, empty, 0, false, String::new(), |_| {})
+}
+
+/// Parses the text content of an element, appending it to `text` and passing
+/// each chunk of text to `chunk` as it is read. Text and CDATA sections are
+/// concatenated.
+/// If `empty`, the element was an empty element and has no content to read.
+/// Otherwise at least `capacity` bytes are reserved for the text once its
+/// first chunk is read. If `lenient`, markup within the element is kept in
+/// the text as written instead of causing an error.
+fn parse_text_chunks<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+    output: &Option<impl Sized>,
+    empty: bool,
+    capacity: usize,
+    lenient: bool,
+    mut text: String,
+    mut chunk: impl FnMut(&str),
 ) -> Result<String, Error> {
     if output.is_some() {
         return Err(Error::Format(parser.reader.buffer_position()));
     }
-    parser.buffer.clear();
-    let text = match parser
-        .reader
-        .read_namespaced_event(
-            &mut parser.buffer,
-            &mut parser.namespace_buffer,
-        )?
-        .1
-    {
-        Event::Text(text) => text.unescape_and_decode(&parser.reader)?,
-        Event::End { .. } => return Ok(String::new()),
-        _ => return Err(Error::Format(parser.reader.buffer_position())),
+    if empty {
+        return Ok(text);
+    }
+    // The names of the elements nested in the element when lenient.
+    let mut nested: Vec<Vec<u8>> = vec![];
+    macro_rules! push {
+        ($($value:expr),*) => {{
+            $(
+                let value: &str = $value;
+                chunk(value);
+                text.reserve(capacity.saturating_sub(text.len()));
+                text.push_str(value);
+            )*
+        }};
+    }
+    loop {
+        match read_event!(parser)?.1 {
+            Event::Text(event) => {
+                let value = unescape_and_decode(&event, &parser.options)?;
+                if text.capacity() == 0 && value.capacity() >= capacity {
+                    chunk(&value);
+                    text = value;
+                } else {
+                    push!(&value);
+                }
+            }
+            Event::CData(event) => {
+                push!(&decode_raw(&event, &parser.options)?)
+            }
+            Event::Start(event) if lenient => {
+                nested.push(event.name().to_vec());
+                push!("<", &decode_raw(&event, &parser.options)?, ">");
+            }
+            Event::Empty(event) if lenient => {
+                push!("<", &decode_raw(&event, &parser.options)?, "/>");
+            }
+            Event::Comment(event) if lenient => {
+                push!("<!--", &decode_raw(&event, &parser.options)?, "-->");
+            }
+            Event::End(event) => match nested.last() {
+                None => return Ok(text),
+                Some(name) if name.as_slice() == event.name() => {
+                    nested.pop();
+                    push!(
+                        "</",
+                        &decode_raw(event.name(), &parser.options)?,
+                        ">"
+                    );
+                }
+                // An element left open within the element, such as `<br>`.
+                Some(_) if event.local_name() == b"text" => return Ok(text),
+                Some(_) => {
+                    push!(
+                        "</",
+                        &decode_raw(event.name(), &parser.options)?,
+                        ">"
+                    );
+                }
+            },
+            Event::Eof => {
+                return Err(Error::UnexpectedEof(
+                    parser.reader.buffer_position(),
+                ))
+            }
+            _ => return Err(Error::Format(parser.reader.buffer_position())),
+        }
+    }
+}
+
+/// The maximum number of bytes reserved for a text from the length declared
+/// in the `bytes` attribute of the `text` element.
+const MAX_DECLARED_CAPACITY: u64 = 1 << 26;
+
+/// Parses the content of the `text` element of a revision, computing
+/// the SHA-1 of the text if enabled. If the text doesn't contain the string
+/// set with [`ParserBuilder::text_contains`], the text is discarded and
+/// the page is marked as unmatched.
+fn parse_revision_text<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+    page: &mut PageState<N>,
+    empty: bool,
+) -> Result<String, Error> {
+    // Taken out of the options while the text is parsed so that it can be
+    // borrowed by the search.
+    let needle = parser.options.text_contains.take();
+    let mut search = needle.as_deref().map(StreamSearch::new);
+    #[cfg(feature = "sha1")]
+    let mut hasher = if parser.options.text_sha1 {
+        Some(sha1_smol::Sha1::new())
+    } else {
+        None
     };
-    parser.buffer.clear();
-    if let Event::End(_) = parser
-        .reader
-        .read_namespaced_event(
-            &mut parser.buffer,
-            &mut parser.namespace_buffer,
-        )?
-        .1
+    let lenient = parser.options.lenient_text;
+    // The buffer given to `Parser::next_text_into` if any.
+    let text = std::mem::take(&mut parser.text_buffer);
+    let start = text.len();
+    // The length declared in the `bytes` attribute is reserved, up to a
+    // limit so that a wrong length doesn't allocate too much.
+    let declared = page
+        .declared_bytes
+        .map_or(0, |bytes| bytes.min(MAX_DECLARED_CAPACITY) as usize);
+    let capacity = parser.options.text_hint.max(start + declared);
+    let text = parse_text_chunks(
+        parser,
+        &page.text,
+        empty,
+        capacity,
+        lenient,
+        text,
+        |chunk| {
+            if let Some(search) = &mut search {
+                search.update(chunk.as_bytes());
+            }
+            #[cfg(feature = "sha1")]
+            {
+                if let Some(hasher) = &mut hasher {
+                    hasher.update(chunk.as_bytes());
+                }
+            }
+        },
+    );
+    let unmatched = matches!(&search, Some(search) if !search.found());
+    parser.options.text_contains = needle;
+    let text = text?;
+    #[cfg(feature = "sha1")]
     {
-        Ok(text)
-    } else {
-        Err(Error::Format(parser.reader.buffer_position()))
+        if let Some(hasher) = hasher {
+            page.text_sha1 = Some(sha1_base36(hasher.digest().bytes()));
+        }
+    }
+    let mut text = text;
+    if unmatched {
+        page.text_unmatched = true;
+        text.truncate(start);
+    }
+    Ok(text)
+}
+
+/// Formats a SHA-1 digest the way MediaWiki does: as a base 36 number with
+/// lowercase letters, padded with zeros to 31 digits.
+#[cfg(feature = "sha1")]
+fn sha1_base36(mut digest: [u8; 20]) -> String {
+    const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut output = [b'0'; 31];
+    for position in (0..output.len()).rev() {
+        let mut remainder = 0u32;
+        for byte in digest.iter_mut() {
+            let value = (remainder << 8) | u32::from(*byte);
+            *byte = (value / 36) as u8;
+            remainder = value % 36;
+        }
+        output[position] = DIGITS[remainder as usize];
+    }
+    output.iter().map(|&digit| char::from(digit)).collect()
+}
+
+/// Parses a SHA-1 digest formatted the way MediaWiki does, as by
+/// [`sha1_base36`], returning `None` if it isn't a base 36 number of up to
+/// 31 digits that fits in 20 bytes.
+fn sha1_from_base36(base36: &str) -> Option<[u8; 20]> {
+    if base36.is_empty() || base36.len() > 31 {
+        return None;
+    }
+    let mut digest = [0u8; 20];
+    for digit in base36.chars() {
+        let mut carry = digit.to_digit(36)?;
+        for byte in digest.iter_mut().rev() {
+            let value = u32::from(*byte) * 36 + carry;
+            *byte = value as u8;
+            carry = value >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(digest)
+}
+
+/// Skips the content of a `text` element up to and including its end tag,
+/// returning the byte offsets of the start and end of the content.
+fn skip_text<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+) -> Result<(usize, usize), Error> {
+    let start = parser.reader.buffer_position();
+    loop {
+        let end = parser.reader.buffer_position();
+        match read_event!(parser)?.1 {
+            Event::End(_) => return Ok((start, end)),
+            Event::Text(_) | Event::CData(_) | Event::Comment(_) => {}
+            Event::Eof => {
+                return Err(Error::UnexpectedEof(
+                    parser.reader.buffer_position(),
+                ))
+            }
+            _ => return Err(Error::Format(parser.reader.buffer_position())),
+        }
     }
 }
 
+/// Skips the content of an element up to and including its end tag.
 fn skip_element<R: BufRead, N: FromNamespaceId>(
     parser: &mut Parser<R, N>,
-) -> Result<(), quick_xml::Error> {
+) -> Result<(), Error> {
     let mut level = 0;
     loop {
-        parser.buffer.clear();
-        match parser
-            .reader
-            .read_namespaced_event(
-                &mut parser.buffer,
-                &mut parser.namespace_buffer,
-            )?
-            .1
-        {
+        match read_event!(parser)?.1 {
             Event::End(_) => {
                 if level == 0 {
                     return Ok(());
@@ -684,6 +4223,13 @@ fn skip_element<R: BufRead, N: FromNamespaceId>(
                 level -= 1;
             }
             Event::Start(_) => level += 1,
+            // An empty element has no end tag, so it leaves the level as it is.
+            Event::Empty(_) => {}
+            Event::Eof => {
+                return Err(Error::UnexpectedEof(
+                    parser.reader.buffer_position(),
+                ))
+            }
             _ => {}
         }
     }
@@ -694,11 +4240,92 @@ Enclose a namespace enum definition to derive the [`FromNamespaceId`] trait
 as well as other [common traits] ([`Debug`], [`Eq`], [`PartialEq`], [`Ord`],
 [`PartialOrd`], [`Clone`], [`Copy`], [`Hash`]) for it.
 
+If one of the variants is marked with `#[default]`, [`Default`] is derived
+as well, returning that variant. This makes [`Page`] implement `Default` with
+the namespace type.
+
+The enum also gets the associated functions `all` and `iter`, which return
+a slice of and an iterator over all variants in the order they are declared,
+for example to list the namespaces in help text, and the method `id`, which
+returns the [`NamespaceId`] of a variant.
+
+```rust
+use parse_mediawiki_dump::{impl_namespace, Page};
+
+impl_namespace! {
+    pub enum Namespace {
+        Talk = 1,
+        #[default]
+        Main = 0,
+    }
+}
+
+assert_eq!(Namespace::default(), Namespace::Main);
+assert_eq!(Page::<Namespace>::default().namespace, Namespace::Main);
+assert_eq!(Namespace::all(), [Namespace::Talk, Namespace::Main]);
+assert_eq!(Namespace::Talk.id(), parse_mediawiki_dump::NamespaceId(1));
+```
+
 [common traits]:
 https://rust-lang.github.io/api-guidelines/interoperability.html#c-common-traits
 */
 #[macro_export]
 macro_rules! impl_namespace {
+    (@impls $namespace:ident { $($variant:ident = $id:literal,)* }) => {
+        impl ::std::convert::TryFrom<::parse_mediawiki_dump::NamespaceId> for $namespace {
+            type Error = &'static str;
+
+            fn try_from(id: ::parse_mediawiki_dump::NamespaceId) -> Result<Self, Self::Error> {
+                match i32::from(id) {
+                    $($id => Ok($namespace::$variant),)*
+                    _ => Err("invalid namespace id"),
+                }
+            }
+        }
+
+        impl $namespace {
+            /// Returns all variants, in the order they are declared.
+            pub fn all() -> &'static [Self] {
+                &[$($namespace::$variant,)*]
+            }
+
+            /// Returns an iterator over all variants, in the order they are
+            /// declared.
+            pub fn iter() -> ::std::iter::Copied<::std::slice::Iter<'static, Self>> {
+                Self::all().iter().copied()
+            }
+
+            /// Returns the namespace id of the variant.
+            pub fn id(&self) -> ::parse_mediawiki_dump::NamespaceId {
+                ::parse_mediawiki_dump::NamespaceId(*self as i32)
+            }
+        }
+    };
+    (
+        $(#[$attribute:meta])*
+        $visibility:vis enum $namespace:ident {
+            $($before:ident = $before_id:literal,)*
+            #[default]
+            $default:ident = $default_id:literal
+            $(, $after:ident = $after_id:literal)* $(,)?
+        }
+    ) => {
+        $(#[$attribute])*
+        #[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, Default)]
+        #[repr(i32)]
+        $visibility enum $namespace {
+            $($before = $before_id,)*
+            #[default]
+            $default = $default_id,
+            $($after = $after_id,)*
+        }
+
+        $crate::impl_namespace!(@impls $namespace {
+            $($before = $before_id,)*
+            $default = $default_id,
+            $($after = $after_id,)*
+        });
+    };
     (
         $(#[$attribute:meta])*
         $visibility:vis enum $namespace:ident {
@@ -712,15 +4339,6 @@ macro_rules! impl_namespace {
             $($variant = $id,)*
         }
 
-        impl ::std::convert::TryFrom<::parse_mediawiki_dump::NamespaceId> for $namespace {
-            type Error = &'static str;
-
-            fn try_from(id: ::parse_mediawiki_dump::NamespaceId) -> Result<Self, Self::Error> {
-                match i32::from(id) {
-                    $($id => Ok($namespace::$variant),)*
-                    _ => Err("invalid namespace id"),
-                }
-            }
-        }
+        $crate::impl_namespace!(@impls $namespace { $($variant = $id,)* });
     };
 }
@@ -0,0 +1,78 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{FromNamespaceId, NamespaceId};
+
+/**
+A namespace that is either of a known type or, if the id can't be converted
+to that type, the raw id.
+
+Used as the namespace type of a parser, this makes parsing lenient about
+namespaces: a page in a namespace that the type `N` has no value for is
+returned with `Unknown` instead of causing [`Error::Namespace`](crate::Error::Namespace),
+so known namespaces can still be matched while unknown ids are kept.
+
+```rust
+use parse_mediawiki_dump::{impl_namespace, MaybeNamespace, NamespaceId};
+
+impl_namespace! {
+    enum Namespace {
+        Main = 0,
+        Talk = 1,
+    }
+}
+
+let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page><ns>1</ns><title>Talk:alpha</title><revision><text/></revision></page>
+    <page><ns>4</ns><title>Project:beta</title><revision><text/></revision></page>
+</mediawiki>"#;
+let namespaces = parse_mediawiki_dump::parse_with_namespace::<_, MaybeNamespace<Namespace>>(
+    dump.as_bytes(),
+)
+.map(|page| page.map(|page| page.namespace))
+.collect::<Result<Vec<_>, _>>()
+.unwrap();
+assert_eq!(
+    namespaces,
+    [
+        MaybeNamespace::Known(Namespace::Talk, NamespaceId(1)),
+        MaybeNamespace::Unknown(NamespaceId(4)),
+    ]
+);
+```
+*/
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
+pub enum MaybeNamespace<N> {
+    /// A namespace that could be converted to the type `N`, with its id.
+    Known(N, NamespaceId),
+
+    /// The id of a namespace that the type `N` has no value for.
+    Unknown(NamespaceId),
+}
+
+impl<N> MaybeNamespace<N> {
+    /// Returns the namespace if it is known.
+    pub fn known(&self) -> Option<&N> {
+        match self {
+            MaybeNamespace::Known(namespace, _) => Some(namespace),
+            MaybeNamespace::Unknown(_) => None,
+        }
+    }
+
+    /// Returns the id of the namespace, whether it is known or not.
+    pub fn raw_id(&self) -> NamespaceId {
+        match self {
+            MaybeNamespace::Known(_, id) | MaybeNamespace::Unknown(id) => *id,
+        }
+    }
+}
+
+impl<N: FromNamespaceId> From<NamespaceId> for MaybeNamespace<N> {
+    fn from(id: NamespaceId) -> Self {
+        match N::from_namespace_id(id) {
+            Some(namespace) => MaybeNamespace::Known(namespace, id),
+            None => MaybeNamespace::Unknown(id),
+        }
+    }
+}
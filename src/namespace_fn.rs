@@ -0,0 +1,105 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{
+    stop_after_error, Error, NamespaceId, Page, Parser, ParserBuilder,
+};
+use std::{io::BufRead, iter::FusedIterator};
+
+/// Parser working as an iterator over pages, in which namespaces are
+/// converted by a function.
+///
+/// Created by [`parse_with_namespace_fn`] and
+/// [`ParserBuilder::parse_with_namespace_fn`].
+pub struct NamespaceFnParser<R: BufRead, F> {
+    parser: Parser<R, NamespaceId>,
+    resolve: F,
+}
+
+impl ParserBuilder {
+    /**
+    Creates a parser for a stream in which namespaces are converted by
+    a function instead of by [`FromNamespaceId`](crate::FromNamespaceId).
+
+    This allows a mapping known only at run time, for example from the
+    `namespaces` element of the `siteinfo` element. A page for which
+    the function returns `None` causes [`Error::Namespace`], as with
+    [`ParserBuilder::parse_with_namespace`].
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>4</ns><title>Project:Alpha</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let names = std::collections::HashMap::from([(4, "Project")]);
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .parse_with_namespace_fn(dump.as_bytes(), |id| {
+            names.get(&id.into_inner()).copied()
+        })
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.namespace, "Project");
+    ```
+    */
+    pub fn parse_with_namespace_fn<R: BufRead, N, F>(
+        self,
+        source: R,
+        resolve: F,
+    ) -> NamespaceFnParser<R, F>
+    where
+        F: FnMut(NamespaceId) -> Option<N>,
+    {
+        NamespaceFnParser {
+            parser: self.parse(source),
+            resolve,
+        }
+    }
+}
+
+/// Creates a parser for a stream in which namespaces are converted by
+/// a function. See [`ParserBuilder::parse_with_namespace_fn`].
+pub fn parse_with_namespace_fn<R: BufRead, N, F>(
+    source: R,
+    resolve: F,
+) -> NamespaceFnParser<R, F>
+where
+    F: FnMut(NamespaceId) -> Option<N>,
+{
+    ParserBuilder::new().parse_with_namespace_fn(source, resolve)
+}
+
+impl<R: BufRead, F> NamespaceFnParser<R, F> {
+    /// Returns the parser of the pages before their namespaces are
+    /// converted, for example to read its [`stats`](Parser::stats).
+    pub fn parser(&self) -> &Parser<R, NamespaceId> {
+        &self.parser
+    }
+}
+
+impl<R: BufRead, N, F> Iterator for NamespaceFnParser<R, F>
+where
+    F: FnMut(NamespaceId) -> Option<N>,
+{
+    type Item = Result<Page<N>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.parser.next()?.and_then(|page| {
+            match (self.resolve)(page.namespace) {
+                Some(namespace) => Ok(page.with_namespace(namespace)),
+                None => {
+                    let error = Error::Namespace {
+                        id: page.namespace,
+                        position: self.parser.reader.buffer_position(),
+                    };
+                    Err(stop_after_error(&mut self.parser, error))
+                }
+            }
+        }))
+    }
+}
+
+impl<R: BufRead, N, F> FusedIterator for NamespaceFnParser<R, F> where
+    F: FnMut(NamespaceId) -> Option<N>
+{
+}
@@ -0,0 +1,49 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{Error, FromNamespaceId, Page, Parser};
+use std::{io::BufRead, iter::FusedIterator};
+
+/// Iterator over pages together with their offsets in the stream.
+///
+/// Created by [`Parser::enumerate_offsets`].
+pub struct EnumerateOffsets<R: BufRead, N>(Parser<R, N>);
+
+impl<R: BufRead, N: FromNamespaceId> Parser<R, N> {
+    /**
+    Converts the parser into an iterator over pages paired with the byte
+    offset of the start tag of their `page` element in the stream.
+
+    The offsets count the bytes read from the source of the parser, starting
+    from zero, so they can be used to build an index of a decompressed dump.
+    Elements before the first page, such as `siteinfo`, don't affect them.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let (offset, page) = parse_mediawiki_dump::parse(dump.as_bytes())
+        .enumerate_offsets()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(offset, dump.find("<page>").unwrap());
+    assert_eq!(page.title, "alpha");
+    ```
+    */
+    pub fn enumerate_offsets(self) -> EnumerateOffsets<R, N> {
+        EnumerateOffsets(self)
+    }
+}
+
+impl<R: BufRead, N: FromNamespaceId> Iterator for EnumerateOffsets<R, N> {
+    type Item = Result<(usize, Page<N>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let page = self.0.next()?;
+        Some(page.map(|page| (self.0.page_start, page)))
+    }
+}
+
+impl<R: BufRead, N: FromNamespaceId> FusedIterator for EnumerateOffsets<R, N> {}
@@ -0,0 +1,72 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::Page;
+use std::cmp::Ordering;
+
+impl<N: Ord> Page<N> {
+    /**
+    Compares pages in the order MediaWiki sorts them by name: by namespace,
+    then by title as stored in the database, with underscores instead of
+    spaces.
+
+    This differs from the implementation of [`Ord`] for `Page`, which
+    compares titles as they are written in the dump, for titles where
+    a space is followed by a character that sorts between a space and
+    an underscore, such as `!` or a digit.
+
+    ```rust
+    use parse_mediawiki_dump::{NamespaceId, Page};
+    let page = |title: &str| Page {
+        namespace: NamespaceId(0),
+        title: title.to_string(),
+        ..Page::default()
+    };
+    assert!(page("A b") < page("A!"));
+    assert!(page("A b").cmp_dump_order(&page("A!")).is_gt());
+    ```
+    */
+    pub fn cmp_dump_order(&self, other: &Self) -> Ordering {
+        self.namespace.cmp(&other.namespace).then_with(|| {
+            database_key(&self.title).cmp(database_key(&other.title))
+        })
+    }
+}
+
+/// Returns the bytes of a title as stored in the database.
+fn database_key(title: &str) -> impl Iterator<Item = u8> + '_ {
+    title
+        .bytes()
+        .map(|byte| if byte == b' ' { b'_' } else { byte })
+}
+
+/**
+Wrapper for a page that compares pages with [`Page::cmp_dump_order`].
+
+Useful for merging sorted streams of pages with a data structure that
+requires [`Ord`], such as [`BinaryHeap`](std::collections::BinaryHeap).
+Only the namespace and the title are compared.
+*/
+#[derive(Debug, Clone)]
+pub struct DumpOrd<N>(pub Page<N>);
+
+impl<N: Ord> PartialEq for DumpOrd<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N: Ord> Eq for DumpOrd<N> {}
+
+impl<N: Ord> PartialOrd for DumpOrd<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N: Ord> Ord for DumpOrd<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp_dump_order(&other.0)
+    }
+}
@@ -0,0 +1,84 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{Error, FromNamespaceId, Page, Parser};
+use std::{
+    io::BufRead,
+    sync::{mpsc::sync_channel, Arc, Mutex},
+};
+
+impl<R: BufRead, N: FromNamespaceId + Send> Parser<R, N> {
+    /**
+    Parses the pages on the calling thread and calls `f` with each of them
+    on a pool of `threads` threads, returning the first error that occurs
+    while parsing.
+
+    The pages are passed to the threads through a channel holding up to
+    twice as many pages as there are threads, so the parser waits for the
+    threads when they fall behind instead of buffering the dump in memory.
+    The pages are processed in no particular order. After an error, no more
+    pages are parsed, and the pages already parsed are processed before
+    the error is returned. All the threads have finished when this method
+    returns.
+
+    # Panics
+
+    Panics if `threads` is 0, or if `f` panics, after the other threads
+    have finished.
+
+    ```rust
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text>beta</text></revision></page>
+        <page><ns>0</ns><title>gamma</title><revision><text>delta</text></revision></page>
+    </mediawiki>"#;
+    let length = AtomicUsize::new(0);
+    parse_mediawiki_dump::parse(dump.as_bytes())
+        .for_each_parallel(4, |page| {
+            length.fetch_add(page.text.len(), Ordering::Relaxed);
+        })
+        .unwrap();
+    assert_eq!(length.into_inner(), 9);
+    ```
+    */
+    pub fn for_each_parallel(
+        self,
+        threads: usize,
+        f: impl Fn(Page<N>) + Sync,
+    ) -> Result<(), Error> {
+        assert!(threads > 0, "the number of threads must be positive");
+        let (sender, receiver) = sync_channel(threads * 2);
+        // Each thread holds a reference to the receiver, so that if all of
+        // them panic, the receiver is dropped and the parser stops instead of
+        // waiting forever.
+        let receiver = Arc::new(Mutex::new(receiver));
+        let f = &f;
+        std::thread::scope(|scope| {
+            for _ in 0..threads {
+                let receiver = receiver.clone();
+                scope.spawn(move || loop {
+                    let page = match receiver.lock() {
+                        Ok(receiver) => receiver.recv(),
+                        Err(_) => return,
+                    };
+                    match page {
+                        Ok(page) => f(page),
+                        Err(_) => return,
+                    }
+                });
+            }
+            drop(receiver);
+            // Moved into the closure so that it is dropped on returning,
+            // which ends the threads before they are joined.
+            let sender = sender;
+            for page in self {
+                if sender.send(page?).is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        })
+    }
+}
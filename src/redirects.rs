@@ -0,0 +1,109 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{Error, FromNamespaceId, MetaParser, Page};
+use std::{collections::HashMap, io::BufRead};
+
+/// The maximum number of redirects followed by [`follow_redirect`].
+pub const MAX_REDIRECT_DEPTH: usize = 16;
+
+/**
+Collects the redirects in a dump into a map from the title of each redirect
+to its target.
+
+Only the metadata of the pages is parsed, so the texts are not kept in memory.
+Combined with [`follow_redirect`], this allows replacing each redirect with
+the page it points to in a second pass over the dump.
+
+```rust
+let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <redirect title="beta" />
+        <revision><text>#REDIRECT [[beta]]</text></revision>
+    </page>
+</mediawiki>"#;
+let redirects = parse_mediawiki_dump::resolve_redirects(
+    parse_mediawiki_dump::parse_meta(dump.as_bytes()),
+)
+.unwrap();
+assert_eq!(redirects["alpha"], "beta");
+```
+*/
+pub fn resolve_redirects<R: BufRead, N: FromNamespaceId>(
+    parser: MetaParser<R, N>,
+) -> Result<HashMap<String, String>, Error> {
+    let mut redirects = HashMap::new();
+    for page in parser {
+        let page = page?;
+        if let Some(target) = page.redirect_title {
+            redirects.insert(page.title, target);
+        }
+    }
+    Ok(redirects)
+}
+
+/**
+Follows a chain of redirects starting from a title, using a map created by
+[`resolve_redirects`].
+
+Returns `None` if the title is not a redirect. Otherwise returns the last
+title in the chain, which is normally a page that is not a redirect.
+If the chain loops back on itself, or is longer than [`MAX_REDIRECT_DEPTH`],
+the last title before the loop or at the maximum depth is returned.
+*/
+pub fn follow_redirect<'a>(
+    redirects: &'a HashMap<String, String>,
+    title: &str,
+) -> Option<&'a str> {
+    let mut target = redirects.get(title)?.as_str();
+    let mut visited = vec![title];
+    while visited.len() < MAX_REDIRECT_DEPTH {
+        match redirects.get(target) {
+            None => break,
+            Some(next) => {
+                visited.push(target);
+                if visited.contains(&next.as_str()) {
+                    break;
+                }
+                target = next;
+            }
+        }
+    }
+    Some(target)
+}
+
+impl<N> Page<N> {
+    /**
+    Returns the target of the redirect split into the title and the
+    fragment after the first `#`, if any, or `None` if the page is not a
+    redirect.
+
+    A redirect can point to a section of a page, as in `Foo#History`, in
+    which case the `redirect_title` field contains the fragment. A title
+    can't contain `#`, so the first one starts the fragment, except as part
+    of a numeric character reference such as `&#35;`, which a title can
+    contain as text.
+
+    ```rust
+    let page = parse_mediawiki_dump::Page::<parse_mediawiki_dump::NamespaceId> {
+        redirect_title: Some("Foo#History".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(page.redirect_target_and_fragment(), Some(("Foo", Some("History"))));
+    ```
+    */
+    pub fn redirect_target_and_fragment(&self) -> Option<(&str, Option<&str>)> {
+        let target = self.redirect_title.as_deref()?;
+        let separator = target
+            .match_indices('#')
+            .map(|(index, _)| index)
+            .find(|&index| !target[..index].ends_with('&'));
+        Some(match separator {
+            None => (target, None),
+            Some(index) => (&target[..index], Some(&target[index + 1..])),
+        })
+    }
+}
@@ -0,0 +1,77 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{Error, FromNamespaceId, NamespaceId, Parser, ParserBuilder};
+use std::io::{BufRead, Seek, SeekFrom};
+
+impl ParserBuilder {
+    /**
+    Creates a parser that resumes parsing a stream at the start tag of
+    a `page` element at `offset`, such as an offset saved from
+    [`Parser::enumerate_offsets`] for a checkpoint.
+
+    Returns [`Error::Format`] if the stream doesn't contain the start tag of
+    a `page` element at the offset, and [`Error::Io`] if seeking fails.
+
+    The root element and the `siteinfo` element, which are before the
+    offset, are not read. Therefore the XML namespaces of elements are not
+    checked, as with [`ParserBuilder::check_xmlns`] disabled, and end tags
+    are not required to match start tags. Positions reported by the parser,
+    in errors as well as offsets, are relative to `offset`.
+    */
+    pub fn parse_from_offset<R: BufRead + Seek, N: FromNamespaceId>(
+        mut self,
+        mut source: R,
+        offset: u64,
+    ) -> Result<Parser<R, N>, Error> {
+        let io_error = |source| Error::Io {
+            source,
+            position: 0,
+        };
+        source.seek(SeekFrom::Start(offset)).map_err(io_error)?;
+        let start = source.fill_buf().map_err(io_error)?;
+        let page = match start.get(..6) {
+            Some([b'<', b'p', b'a', b'g', b'e', next]) => {
+                *next == b'>' || next.is_ascii_whitespace()
+            }
+            _ => false,
+        };
+        if !page {
+            return Err(Error::Format(0));
+        }
+        self.check_xmlns = false;
+        let mut parser = self.parse_with_namespace(source);
+        parser.reader.check_end_names(false);
+        parser.started = true;
+        Ok(parser)
+    }
+}
+
+/**
+Creates a parser that resumes parsing a stream at the start tag of a `page`
+element at `offset`, in which namespaces are represented as
+[`NamespaceId`]. See [`ParserBuilder::parse_from_offset`].
+
+```rust
+use std::io::Cursor;
+
+let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+    <page><ns>0</ns><title>beta</title><revision><text/></revision></page>
+</mediawiki>"#;
+let offset = dump.rfind("<page>").unwrap() as u64;
+let titles = parse_mediawiki_dump::parse_from_offset(Cursor::new(dump), offset)
+    .unwrap()
+    .map(|page| page.map(|page| page.title))
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+assert_eq!(titles, ["beta"]);
+```
+*/
+pub fn parse_from_offset<R: BufRead + Seek>(
+    source: R,
+    offset: u64,
+) -> Result<Parser<R, NamespaceId>, Error> {
+    ParserBuilder::new().parse_from_offset(source, offset)
+}
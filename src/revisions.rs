@@ -0,0 +1,377 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{
+    default_namespace, namespace_in_range, next_page_start, page_error,
+    parse_page_child, parse_revision, read_page_child, skip_element,
+    stop_after_error, take_current_page, Deleted, Error, FromNamespaceId,
+    NamespaceId, PageChildElement, PageMeta, PageState, Parser,
+};
+use std::{cmp::Ordering, io::BufRead, iter::FusedIterator};
+
+/**
+Parsed revision.
+
+Parsed from a `revision` element by [`RevisionsFlat`]. The fields are
+the same as the fields of [`Page`](crate::Page) that are parsed from
+the `revision` element.
+*/
+#[derive(Debug, Clone)]
+pub struct Revision {
+    /// The length of the text in bytes as declared in the `bytes` attribute
+    /// of the `text` element, if any.
+    pub declared_bytes: Option<u64>,
+
+    /// The SHA-1 of the text as declared in the `sha1` attribute of the
+    /// `text` element, if any.
+    pub declared_sha1: Option<String>,
+
+    /// Which fields of the revision have been deleted.
+    pub deleted: Deleted,
+
+    /// The format of the revision if any.
+    pub format: Option<String>,
+
+    /// Whether the revision is marked as a minor edit with a `minor` element.
+    pub minor: bool,
+
+    /// The model of the revision if any.
+    pub model: Option<String>,
+
+    /// The format as written in the dump, if enabled with
+    /// [`ParserBuilder::normalize_model`](crate::ParserBuilder::normalize_model).
+    pub raw_format: Option<String>,
+
+    /// The model as written in the dump, if enabled with
+    /// [`ParserBuilder::normalize_model`](crate::ParserBuilder::normalize_model).
+    pub raw_model: Option<String>,
+
+    /// The timestamp of the revision as written in the `timestamp` element,
+    /// if any.
+    pub raw_timestamp: Option<String>,
+
+    /// The SHA-1 of the text from the `sha1` element, if any.
+    pub sha1: Option<String>,
+
+    /// The text of the revision.
+    pub text: String,
+
+    /// The location of the text in an external store, if any.
+    pub text_location: Option<String>,
+
+    /// The SHA-1 of the text computed while parsing, if enabled with
+    /// [`ParserBuilder::text_sha1`](crate::ParserBuilder::text_sha1).
+    #[cfg(feature = "sha1")]
+    pub text_sha1: Option<String>,
+
+    /// The byte offsets of the content of the `text` element, if enabled
+    /// with [`ParserBuilder::text_spans`](crate::ParserBuilder::text_spans).
+    pub text_span: Option<(usize, usize)>,
+
+    /// Whether the `text` element is written as `<text/>`, if enabled with
+    /// [`ParserBuilder::record_empty_text_element`](crate::ParserBuilder::record_empty_text_element).
+    pub text_was_empty_element: bool,
+}
+
+impl Revision {
+    /// Returns whether the revision is marked as a minor edit.
+    pub fn is_minor(&self) -> bool {
+        self.minor
+    }
+
+    /**
+    Returns the timestamp of the revision, or `None` if the revision has no
+    timestamp or it is not in the format of RFC 3339, such as
+    `2001-01-15T13:15:00Z`. Only available with the `chrono` feature.
+    */
+    #[cfg(feature = "chrono")]
+    pub fn timestamp(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let timestamp = self.raw_timestamp.as_ref()?;
+        chrono::DateTime::parse_from_rfc3339(timestamp.trim())
+            .ok()
+            .map(|timestamp| timestamp.with_timezone(&chrono::Utc))
+    }
+
+    /**
+    Compares revisions by timestamp, with revisions without a timestamp
+    first.
+
+    The timestamps are compared as they are written in the dump, which is
+    the order of time for the format MediaWiki writes, such as
+    `2001-01-15T13:15:00Z`.
+    */
+    pub fn cmp_timestamp(&self, other: &Self) -> Ordering {
+        self.raw_timestamp.cmp(&other.raw_timestamp)
+    }
+}
+
+/**
+Wrapper for a revision that compares revisions with
+[`Revision::cmp_timestamp`].
+
+Useful for sorting the revisions of a page, which are not necessarily in
+order of time in a dump, or for merging them with a data structure that
+requires [`Ord`], such as [`BinaryHeap`](std::collections::BinaryHeap).
+Only the timestamps are compared.
+*/
+#[derive(Debug, Clone)]
+pub struct ByTimestamp(pub Revision);
+
+impl PartialEq for ByTimestamp {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ByTimestamp {}
+
+impl PartialOrd for ByTimestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ByTimestamp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp_timestamp(&other.0)
+    }
+}
+
+/// Iterator over the revisions of the pages in a dump.
+///
+/// Created by [`Parser::revisions_flat`] and [`parse_revisions_flat`].
+pub struct RevisionsFlat<R: BufRead, N> {
+    /// The fields of the current page parsed before its first revision.
+    page: Option<PageState<N>>,
+    parser: Parser<R, N>,
+    /// The number of revisions of the current page returned so far.
+    revisions: usize,
+}
+
+impl<R: BufRead, N: FromNamespaceId + Clone> Parser<R, N> {
+    /**
+    Converts the parser into an iterator over every revision of every page,
+    each paired with the metadata of its page.
+
+    Unlike iterating over pages, this accepts pages with any number of
+    revisions, as in dumps with the full history of each page. The revisions
+    are parsed one at a time, so a page with many revisions is never held in
+    memory as a whole. The children of the `page` element other than
+    `revision` must come before the first `revision` element, as they do in
+    the schema. Pages without revisions are skipped.
+    */
+    pub fn revisions_flat(mut self) -> RevisionsFlat<R, N> {
+        RevisionsFlat {
+            // The rest of a page read with `Parser::next_title` is parsed
+            // for its revisions.
+            page: take_current_page(&mut self),
+            parser: self,
+            revisions: 0,
+        }
+    }
+}
+
+/**
+Creates an iterator over every revision of every page in a stream, each
+paired with the metadata of its page. See [`Parser::revisions_flat`].
+
+```rust
+let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision><text>beta</text></revision>
+        <revision><text>gamma</text></revision>
+    </page>
+</mediawiki>"#;
+let texts = parse_mediawiki_dump::parse_revisions_flat(dump.as_bytes())
+    .map(|item| item.map(|(page, revision)| (page.title, revision.text)))
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+assert_eq!(
+    texts,
+    [
+        ("alpha".to_string(), "beta".to_string()),
+        ("alpha".to_string(), "gamma".to_string()),
+    ]
+);
+```
+*/
+pub fn parse_revisions_flat<R: BufRead>(
+    source: R,
+) -> RevisionsFlat<R, NamespaceId> {
+    crate::parse(source).revisions_flat()
+}
+
+impl<R: BufRead, N: FromNamespaceId + Clone> RevisionsFlat<R, N> {
+    /**
+    Skips the rest of the page of the last revision returned, so that the
+    next item is the first revision of the next page.
+
+    This allows deciding from the metadata of a page or its first revisions
+    that the rest of its revisions aren't needed, without parsing them.
+    Returns [`Error::NotInPage`] if the iterator is not within a page, as
+    before the first revision or after the last revision of a page has been
+    returned and the end of the page has been read, like
+    [`Parser::skip_current_page`].
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page>
+            <ns>0</ns>
+            <title>alpha</title>
+            <revision><text>beta</text></revision>
+            <revision><text>gamma</text></revision>
+        </page>
+        <page>
+            <ns>0</ns>
+            <title>delta</title>
+            <revision><text>epsilon</text></revision>
+        </page>
+    </mediawiki>"#;
+    let mut revisions = parse_mediawiki_dump::parse_revisions_flat(dump.as_bytes());
+    let (page, _) = revisions.next().unwrap().unwrap();
+    assert_eq!(page.title, "alpha");
+    revisions.skip_current_page().unwrap();
+    let (page, revision) = revisions.next().unwrap().unwrap();
+    assert_eq!((&*page.title, &*revision.text), ("delta", "epsilon"));
+    ```
+    */
+    pub fn skip_current_page(&mut self) -> Result<(), Error> {
+        if self.page.take().is_none() {
+            return Err(Error::NotInPage(self.parser.reader.buffer_position()));
+        }
+        skip_element(&mut self.parser)
+            .map_err(|error| stop_after_error(&mut self.parser, error))
+    }
+}
+
+impl<R: BufRead, N: FromNamespaceId + Clone> Iterator for RevisionsFlat<R, N> {
+    type Item = Result<(PageMeta<N>, Revision), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match next_revision(self) {
+            Err(error) => {
+                self.page = None;
+                Some(Err(stop_after_error(&mut self.parser, error)))
+            }
+            Ok(item) => Ok(item).transpose(),
+        }
+    }
+}
+
+impl<R: BufRead, N: FromNamespaceId + Clone> FusedIterator
+    for RevisionsFlat<R, N>
+{
+}
+
+fn next_revision<R: BufRead, N: FromNamespaceId + Clone>(
+    revisions: &mut RevisionsFlat<R, N>,
+) -> Result<Option<(PageMeta<N>, Revision)>, Error> {
+    let parser = &mut revisions.parser;
+    loop {
+        let page = match &mut revisions.page {
+            Some(page) => page,
+            None => {
+                if !next_page_start(parser)? {
+                    return Ok(None);
+                }
+                revisions.revisions = 0;
+                revisions.page.get_or_insert_with(PageState::new)
+            }
+        };
+        let result = match read_page_child(parser, page) {
+            Err(error) => Err(error),
+            Ok(None) => {
+                revisions.page = None;
+                continue;
+            }
+            Ok(Some((PageChildElement::Revision, empty))) => {
+                match parser.options.max_revisions_per_page {
+                    Some(limit) if revisions.revisions >= limit => {
+                        let position = parser.reader.buffer_position();
+                        let error = Error::TooManyRevisions { limit, position };
+                        let error = page_error(parser, page, error);
+                        revisions.page = None;
+                        if !empty {
+                            skip_element(parser)?;
+                        }
+                        skip_element(parser)?;
+                        return Err(error);
+                    }
+                    _ => {
+                        revisions.revisions += 1;
+                        parse_flat_revision(parser, page, empty)
+                    }
+                }
+            }
+            Ok(Some((element, empty))) => {
+                match parse_page_child(parser, page, element, empty) {
+                    Ok(()) if !namespace_in_range(parser, page) => {
+                        revisions.page = None;
+                        skip_element(parser)?;
+                        continue;
+                    }
+                    result => result.map(|()| None),
+                }
+            }
+        };
+        match result {
+            Err(error) => return Err(page_error(parser, page, error)),
+            Ok(None) => {}
+            Ok(Some(item)) => return Ok(Some(item)),
+        }
+    }
+}
+
+/// Parses a `revision` element after its start tag, pairing it with
+/// the metadata of its page.
+fn parse_flat_revision<R: BufRead, N: FromNamespaceId + Clone>(
+    parser: &mut Parser<R, N>,
+    page: &mut PageState<N>,
+    empty: bool,
+) -> Result<Option<(PageMeta<N>, Revision)>, Error> {
+    default_namespace(parser, page)?;
+    let meta = match page {
+        PageState {
+            id,
+            namespace: Some(namespace),
+            redirect_title,
+            title: Some(title),
+            ..
+        } => PageMeta {
+            page_id: *id,
+            namespace: namespace.clone(),
+            redirect_title: redirect_title.clone(),
+            title: title.clone(),
+        },
+        _ => return Err(Error::Format(parser.reader.buffer_position())),
+    };
+    if empty {
+        return Err(Error::Format(parser.reader.buffer_position()));
+    }
+    let mut revision = PageState::new();
+    parse_revision(parser, &mut revision)?;
+    Ok(Some((
+        meta,
+        Revision {
+            declared_bytes: revision.declared_bytes,
+            declared_sha1: revision.declared_sha1,
+            deleted: revision.deleted,
+            format: revision.format,
+            minor: revision.minor,
+            model: revision.model,
+            raw_format: revision.raw_format,
+            raw_model: revision.raw_model,
+            raw_timestamp: revision.timestamp,
+            sha1: revision.sha1,
+            text: revision.text.unwrap_or_default(),
+            text_location: revision.text_location,
+            #[cfg(feature = "sha1")]
+            text_sha1: revision.text_sha1,
+            text_span: revision.text_span,
+            text_was_empty_element: revision.text_was_empty_element,
+        },
+    )))
+}
@@ -0,0 +1,65 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+/// Searches for a byte string in text given in chunks, finding matches that
+/// span chunks without keeping more than the length of the needle of
+/// earlier chunks.
+pub(crate) struct StreamSearch<'a> {
+    found: bool,
+    needle: &'a [u8],
+    /// The end of the text seen so far, shorter than the needle.
+    tail: Vec<u8>,
+}
+
+impl<'a> StreamSearch<'a> {
+    pub(crate) fn new(needle: &'a [u8]) -> Self {
+        Self {
+            found: needle.is_empty(),
+            needle,
+            tail: vec![],
+        }
+    }
+
+    /// Returns whether the needle has been found in the chunks so far.
+    pub(crate) fn found(&self) -> bool {
+        self.found
+    }
+
+    /// Searches the next chunk of text. Does nothing once the needle has
+    /// been found.
+    pub(crate) fn update(&mut self, chunk: &[u8]) {
+        if self.found {
+            return;
+        }
+        let keep = self.needle.len() - 1;
+        // Matches that start in the tail and end in the chunk.
+        if !self.tail.is_empty() {
+            let start = self.tail.len();
+            self.tail.extend_from_slice(&chunk[..keep.min(chunk.len())]);
+            if contains(&self.tail, self.needle) {
+                self.found = true;
+                return;
+            }
+            self.tail.truncate(start);
+        }
+        if contains(chunk, self.needle) {
+            self.found = true;
+            return;
+        }
+        if chunk.len() >= keep {
+            self.tail.clear();
+            self.tail.extend_from_slice(&chunk[chunk.len() - keep..]);
+        } else {
+            self.tail.extend_from_slice(chunk);
+            let excess = self.tail.len().saturating_sub(keep);
+            self.tail.drain(..excess);
+        }
+    }
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
@@ -0,0 +1,215 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{
+    parse_text, skip_element, source::next_event, Error, FromNamespaceId,
+    NamespaceId, Parser,
+};
+use quick_xml::events::Event;
+use std::{collections::HashMap, io::BufRead};
+
+/**
+Information about the wiki, as given by the `siteinfo` element before the
+pages of a dump.
+
+Only parsed if enabled with
+[`ParserBuilder::capture_site_info`](crate::ParserBuilder::capture_site_info),
+and available from [`Parser::site_info`] once the parser has read past it.
+*/
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct SiteInfo {
+    /// The alternative names of namespaces as pairs of id and name, from the
+    /// `namespacealiases` element, such as `WP` for the project namespace
+    /// of the English Wikipedia. Empty if the element is missing.
+    pub aliases: Vec<(i32, String)>,
+
+    /// The names of the namespaces as pairs of id and name, from the
+    /// `namespaces` element. The name of the main namespace is empty.
+    pub namespaces: Vec<(i32, String)>,
+
+    /// The name of the wiki, from the `sitename` element, if any.
+    pub sitename: Option<String>,
+}
+
+impl<R: BufRead, N> Parser<R, N> {
+    /// Returns the information about the wiki from the `siteinfo` element,
+    /// if enabled with
+    /// [`ParserBuilder::capture_site_info`](crate::ParserBuilder::capture_site_info)
+    /// and the element has been read, which happens when the first page is
+    /// parsed.
+    pub fn site_info(&self) -> Option<&SiteInfo> {
+        self.site_info.as_ref()
+    }
+}
+
+/**
+Namespaces of a wiki as given in its dump, for resolving namespace names
+that aren't known in advance.
+
+Created from the [`SiteInfo`] of a dump. Names are looked up the way
+MediaWiki recognizes namespace prefixes of titles: without regard to case,
+and with underscores in place of spaces. Aliases are recognized as well as
+the names of the namespaces.
+
+```rust
+let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <siteinfo>
+        <namespaces>
+            <namespace key="0" />
+            <namespace key="4">Wikipedia</namespace>
+        </namespaces>
+        <namespacealiases>
+            <namespacealias key="4">WP</namespacealias>
+        </namespacealiases>
+    </siteinfo>
+    <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+</mediawiki>"#;
+let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+    .capture_site_info(true)
+    .parse(dump.as_bytes());
+parser.next().unwrap().unwrap();
+let namespaces =
+    parse_mediawiki_dump::DynamicNamespaces::new(parser.site_info().unwrap());
+assert_eq!(namespaces.id("wp"), Some(parse_mediawiki_dump::NamespaceId(4)));
+assert_eq!(namespaces.name(parse_mediawiki_dump::NamespaceId(4)), Some("Wikipedia"));
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct DynamicNamespaces {
+    /// The ids of the namespaces by normalized name and alias.
+    ids: HashMap<String, i32>,
+    /// The names of the namespaces by id.
+    names: HashMap<i32, String>,
+}
+
+impl DynamicNamespaces {
+    /// Creates the namespaces given by the `siteinfo` element of a dump.
+    pub fn new(site_info: &SiteInfo) -> Self {
+        let mut namespaces = Self::default();
+        for (id, name) in &site_info.namespaces {
+            namespaces.names.insert(*id, name.clone());
+        }
+        // The names of the namespaces take precedence over aliases.
+        for (id, name) in site_info.aliases.iter().chain(&site_info.namespaces)
+        {
+            namespaces.ids.insert(normalize(name), *id);
+        }
+        namespaces
+    }
+
+    /// Returns the id of the namespace with the given name or alias, if any.
+    pub fn id(&self, name: &str) -> Option<NamespaceId> {
+        self.ids.get(&normalize(name)).map(|id| NamespaceId(*id))
+    }
+
+    /// Returns the name of the namespace with the given id, if any.
+    pub fn name(&self, id: NamespaceId) -> Option<&str> {
+        self.names.get(&id.0).map(String::as_str)
+    }
+}
+
+impl From<&SiteInfo> for DynamicNamespaces {
+    fn from(site_info: &SiteInfo) -> Self {
+        Self::new(site_info)
+    }
+}
+
+/// Normalizes a namespace name for lookup.
+fn normalize(name: &str) -> String {
+    name.trim().replace(' ', "_").to_lowercase()
+}
+
+/// Parses the children of a `siteinfo` element after its start tag, up to
+/// and including its end tag. Unknown children are skipped.
+pub(crate) fn parse_site_info<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+) -> Result<SiteInfo, Error> {
+    let mut site_info = SiteInfo::default();
+    loop {
+        let (accepted, event) = next_event(
+            &mut parser.reader,
+            &mut parser.buffer,
+            &mut parser.namespace_buffer,
+            &parser.options,
+        )?;
+        let empty = matches!(event, Event::Empty(_));
+        let name = match event {
+            Event::End(_) => return Ok(site_info),
+            Event::Start(event) | Event::Empty(event) if accepted => {
+                event.local_name().to_vec()
+            }
+            Event::Start(_) => {
+                skip_element(parser)?;
+                continue;
+            }
+            Event::Eof => {
+                return Err(Error::UnexpectedEof(
+                    parser.reader.buffer_position(),
+                ))
+            }
+            _ => continue,
+        };
+        match &*name {
+            b"namespacealiases" if !empty => {
+                parse_namespaces(parser, &mut site_info.aliases)?
+            }
+            b"namespaces" if !empty => {
+                parse_namespaces(parser, &mut site_info.namespaces)?
+            }
+            b"sitename" => {
+                site_info.sitename =
+                    Some(parse_text(parser, &site_info.sitename, empty)?)
+            }
+            _ => {
+                if !empty {
+                    skip_element(parser)?
+                }
+            }
+        }
+    }
+}
+
+/// Parses the children of a `namespaces` or `namespacealiases` element after
+/// its start tag, up to and including its end tag. Each child gives the id
+/// of a namespace in its `key` attribute and a name in its text content.
+fn parse_namespaces<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+    namespaces: &mut Vec<(i32, String)>,
+) -> Result<(), Error> {
+    loop {
+        let (accepted, event) = next_event(
+            &mut parser.reader,
+            &mut parser.buffer,
+            &mut parser.namespace_buffer,
+            &parser.options,
+        )?;
+        let empty = matches!(event, Event::Empty(_));
+        let key = match event {
+            Event::End(_) => return Ok(()),
+            Event::Start(event) | Event::Empty(event) if accepted => event
+                .attributes()
+                .filter_map(|r| r.ok())
+                .find(|attribute| attribute.key == b"key")
+                .and_then(|attribute| {
+                    std::str::from_utf8(&attribute.value).ok()?.parse().ok()
+                }),
+            Event::Start(_) => {
+                skip_element(parser)?;
+                continue;
+            }
+            Event::Eof => {
+                return Err(Error::UnexpectedEof(
+                    parser.reader.buffer_position(),
+                ))
+            }
+            _ => continue,
+        };
+        let key = match key {
+            None => return Err(Error::Format(parser.reader.buffer_position())),
+            Some(key) => key,
+        };
+        let name = parse_text(parser, &None::<String>, empty)?;
+        namespaces.push((key, name));
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::Page;
+
+/// The names of the magic word setting the default sort key, as in the
+/// English localization of MediaWiki.
+const DEFAULT_SORT: &[&str] =
+    &["DEFAULTSORT:", "DEFAULTSORTKEY:", "DEFAULTCATEGORYSORT:"];
+
+impl<N> Page<N> {
+    /**
+    Returns the default sort key of the page given by the
+    [`DEFAULTSORT`](https://www.mediawiki.org/wiki/Help:Magic_words#DEFAULTSORT)
+    magic word in its text, if any.
+
+    The text is scanned for `{{DEFAULTSORT:...}}` and its aliases
+    `DEFAULTSORTKEY` and `DEFAULTCATEGORYSORT` without parsing the wiki text.
+    If the magic word occurs more than once, the last one is used, as
+    MediaWiki does, unless it has the option `noreplace`. To stay on the safe
+    side, `None` is returned for a key that contains templates or parameters,
+    since its value can't be known without expanding them, and for an empty
+    key. Magic words within comments or `nowiki` tags are not recognized as
+    such by MediaWiki but are found by this scan.
+
+    Categories of a page without a default sort key are sorted by the title
+    of the page without its namespace prefix.
+
+    ```rust
+    let page = parse_mediawiki_dump::Page::<parse_mediawiki_dump::NamespaceId> {
+        text: "[[Category:People]]\n{{DEFAULTSORT:Doe, John}}".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(page.default_sort(), Some("Doe, John"));
+    ```
+    */
+    pub fn default_sort(&self) -> Option<&str> {
+        let mut result = None;
+        let mut rest = &*self.text;
+        while let Some(start) = rest.find("{{") {
+            rest = &rest[start + 2..];
+            let name = rest.trim_start();
+            let key = match DEFAULT_SORT
+                .iter()
+                .find_map(|magic_word| name.strip_prefix(magic_word))
+            {
+                None => continue,
+                Some(key) => key,
+            };
+            let end = match key.find("}}") {
+                None => break,
+                Some(end) => end,
+            };
+            rest = &key[end + 2..];
+            // The key can be followed by the option `noerror` or
+            // `noreplace`, which keeps a key set earlier.
+            let mut arguments = key[..end].split('|');
+            let key = arguments.next().unwrap_or_default().trim();
+            if result.is_some()
+                && arguments.any(|option| option.trim() == "noreplace")
+            {
+                continue;
+            }
+            result = if key.is_empty() || key.contains("{{") {
+                None
+            } else {
+                Some(key)
+            };
+        }
+        result
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+//! Reading events from the stream and decoding text as set with the options
+//! of the parser.
+
+use crate::{Error, ParserBuilder};
+use quick_xml::{
+    events::{BytesText, Event},
+    Reader,
+};
+use std::{borrow::Cow, io::BufRead};
+
+/// Reads the next event into `buffer`. The first element of the result tells
+/// whether the element of a start or end tag is in the XML namespace of an
+/// accepted schema version.
+pub(crate) fn next_event<'a, R: BufRead>(
+    reader: &mut Reader<R>,
+    buffer: &'a mut Vec<u8>,
+    namespace_buffer: &mut Vec<u8>,
+    options: &ParserBuilder,
+) -> Result<(bool, Event<'a>), Error> {
+    buffer.clear();
+    let result = if options.check_xmlns {
+        reader.read_namespaced_event(buffer, namespace_buffer).map(
+            |(namespace, event)| (options.match_namespace(namespace), event),
+        )
+    } else {
+        reader.read_event(buffer).map(|event| (true, event))
+    };
+    result.map_err(|error| match error {
+        quick_xml::Error::Io(source) => Error::Io {
+            source,
+            position: reader.buffer_position(),
+        },
+        error => Error::XmlReader(error),
+    })
+}
+
+/// Decodes raw bytes from the stream, such as the name of an attribute or the
+/// content of a CDATA section, from the encoding set with
+/// `ParserBuilder::encoding` if any, otherwise from UTF-8.
+pub(crate) fn decode_raw<'a>(
+    bytes: &'a [u8],
+    options: &ParserBuilder,
+) -> Result<Cow<'a, str>, Error> {
+    #[cfg(feature = "encoding_rs")]
+    {
+        if let Some(encoding) = options.encoding {
+            return Ok(encoding.decode_without_bom_handling(bytes).0);
+        }
+    }
+    #[cfg(not(feature = "encoding_rs"))]
+    let _ = options;
+    Ok(Cow::Borrowed(decode_utf8(bytes)?))
+}
+
+/// Unescapes and decodes escaped bytes from the stream, such as text content
+/// or the value of an attribute. Entity references are kept as they are if
+/// disabled with [`ParserBuilder::decode_entities`].
+pub(crate) fn unescape_and_decode(
+    bytes: &[u8],
+    options: &ParserBuilder,
+) -> Result<String, Error> {
+    if !options.decode_entities {
+        return Ok(decode_raw(bytes, options)?.into_owned());
+    }
+    // Character references are unescaped after decoding, so that the
+    // characters they give are not decoded again.
+    let decoded = decode_raw(bytes, options)?;
+    let text = BytesText::from_escaped(decoded.as_bytes());
+    let unescaped = text.unescaped()?;
+    Ok(decode_utf8(&unescaped)?.to_owned())
+}
+
+fn decode_utf8(bytes: &[u8]) -> Result<&str, Error> {
+    Ok(std::str::from_utf8(bytes).map_err(quick_xml::Error::Utf8)?)
+}
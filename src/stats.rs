@@ -0,0 +1,129 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{MetaParser, NamespaceId, Parser};
+use std::io::BufRead;
+
+/**
+Counts of what a parser has returned so far, returned by [`Parser::stats`].
+
+Together with the size of a compressed dump, this gives the compression
+ratio and the average length of the pages.
+*/
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct ParserStats {
+    /// The number of bytes of the stream read so far, which is the size of
+    /// the decompressed XML up to the current position.
+    pub bytes_read: usize,
+
+    /// The number of pages returned.
+    pub pages: usize,
+
+    /// The number of pages returned that are redirects.
+    pub redirects: usize,
+
+    /// The total length in bytes of the text of the pages returned, after
+    /// unescaping.
+    ///
+    /// Texts that are not read, as with
+    /// [`ParserBuilder::text_spans`](crate::ParserBuilder::text_spans) or
+    /// when parsing only the metadata of pages, are not counted.
+    pub text_bytes: usize,
+}
+
+impl ParserStats {
+    /// Counts a page returned by the parser.
+    pub(crate) fn record(&mut self, redirect: bool, text_bytes: usize) {
+        self.pages += 1;
+        self.redirects += usize::from(redirect);
+        self.text_bytes += text_bytes;
+    }
+}
+
+impl<R: BufRead, N> Parser<R, N> {
+    /**
+    Returns counts of the pages and text returned by the parser so far.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text>beta</text></revision></page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    parser.by_ref().for_each(drop);
+    let stats = parser.stats();
+    assert_eq!((stats.pages, stats.text_bytes), (1, 4));
+    assert_eq!(stats.bytes_read, dump.len());
+    ```
+    */
+    pub fn stats(&self) -> ParserStats {
+        ParserStats {
+            bytes_read: self.reader.buffer_position(),
+            ..self.stats
+        }
+    }
+
+    /**
+    Estimates the total number of pages in a stream of `total_bytes` bytes
+    of decompressed XML from the average size of the pages returned so far,
+    or returns `None` if no page has been returned yet.
+
+    This allows showing progress as "page 12,345 of about 6.2 million"
+    without scanning the stream in advance. The estimate includes the
+    `siteinfo` element in the size of the pages, so it is too low at first
+    and gets closer as more pages are read.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text>beta</text></revision></page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    assert_eq!(parser.estimated_total(1_000_000), None);
+    parser.next().unwrap().unwrap();
+    assert!(parser.estimated_total(1_000_000).unwrap() > 1_000);
+    ```
+    */
+    pub fn estimated_total(&self, total_bytes: u64) -> Option<u64> {
+        let bytes_read = self.reader.buffer_position() as u64;
+        if self.stats.pages == 0 || bytes_read == 0 {
+            return None;
+        }
+        let pages = self.stats.pages as u128;
+        Some((u128::from(total_bytes) * pages / u128::from(bytes_read)) as u64)
+    }
+
+    /**
+    Returns the namespace of the last page returned by the parser, if any.
+
+    ```rust
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+        <page><ns>10</ns><title>Template:beta</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    assert_eq!(parser.last_namespace(), None);
+    parser.next().unwrap().unwrap();
+    assert_eq!(
+        parser.last_namespace(),
+        Some(parse_mediawiki_dump::NamespaceId(0))
+    );
+    ```
+    */
+    pub fn last_namespace(&self) -> Option<NamespaceId> {
+        self.last_namespace
+    }
+}
+
+impl<R: BufRead, N> MetaParser<R, N> {
+    /// Returns counts of the pages returned by the parser so far. Texts are
+    /// not read, so `text_bytes` is zero.
+    pub fn stats(&self) -> ParserStats {
+        self.0.stats()
+    }
+
+    /// Returns the namespace of the last page returned by the parser, if
+    /// any.
+    pub fn last_namespace(&self) -> Option<NamespaceId> {
+        self.0.last_namespace()
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::Page;
+use std::io::{BufRead, Read};
+
+/**
+Reader over the text of a page in chunks, returned by [`Page::text_reader`].
+
+Each chunk returned by [`BufRead::fill_buf`] is at most the chunk size given
+to [`Page::text_reader`] and ends at a character boundary, so it is always
+valid UTF-8 and can be passed to a tokenizer as a string without checking
+for characters split across chunks, unless the previous chunk was consumed
+only in part within a character.
+*/
+#[derive(Debug, Clone)]
+pub struct TextReader<'a> {
+    chunk_size: usize,
+    /// The rest of the text.
+    text: &'a [u8],
+}
+
+impl<N> Page<N> {
+    /**
+    Returns a reader over the text of the page in chunks of at most
+    `chunk_size` bytes, ending at character boundaries, for feeding the text
+    to a tokenizer incrementally.
+
+    The chunks are taken from the `text` field, since the parser returns
+    complete pages, so this bounds the size of the input given to the
+    tokenizer at once rather than the memory used for the text. A chunk is
+    longer than `chunk_size` only if `chunk_size` is shorter than
+    a character, in which case the chunk is that character.
+
+    # Panics
+
+    Panics if `chunk_size` is 0.
+
+    ```rust
+    use std::io::BufRead;
+
+    let page = parse_mediawiki_dump::Page::<parse_mediawiki_dump::NamespaceId> {
+        text: "alpha\nbéta".to_string(),
+        ..Default::default()
+    };
+    let mut reader = page.text_reader(7);
+    let mut chunks = vec![];
+    loop {
+        let chunk = reader.fill_buf().unwrap();
+        if chunk.is_empty() {
+            break;
+        }
+        chunks.push(std::str::from_utf8(chunk).unwrap().to_string());
+        let length = chunk.len();
+        reader.consume(length);
+    }
+    assert_eq!(chunks, ["alpha\nb", "éta"]);
+    ```
+    */
+    pub fn text_reader(&self, chunk_size: usize) -> TextReader<'_> {
+        assert!(chunk_size > 0, "the chunk size must be positive");
+        TextReader {
+            chunk_size,
+            text: self.text.as_bytes(),
+        }
+    }
+}
+
+impl Read for TextReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let chunk = self.fill_buf()?;
+        let length = chunk.len().min(buf.len());
+        buf[..length].copy_from_slice(&chunk[..length]);
+        self.consume(length);
+        Ok(length)
+    }
+}
+
+impl BufRead for TextReader<'_> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        let text = self.text;
+        let is_boundary =
+            |index: usize| index == text.len() || text[index] & 0xc0 != 0x80;
+        let mut end = self.chunk_size.min(text.len());
+        while end > 0 && !is_boundary(end) {
+            end -= 1;
+        }
+        if end == 0 && !text.is_empty() {
+            end = 1;
+            while !is_boundary(end) {
+                end += 1;
+            }
+        }
+        Ok(&text[..end])
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.text = &self.text[amount.min(self.text.len())..];
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{
+    parse_text, skip_element, source::next_event, Error, FromNamespaceId,
+    Parser,
+};
+use quick_xml::events::Event;
+use std::io::BufRead;
+
+/**
+Threading metadata of a page of the
+[LiquidThreads](https://www.mediawiki.org/wiki/Extension:LiquidThreads)
+extension, as given by the `DiscussionThreading` element of the page.
+
+Only parsed if enabled with
+[`ParserBuilder::capture_threads`](crate::ParserBuilder::capture_threads).
+Each field is parsed from the child element named in its documentation,
+and is `None` if the element is missing.
+*/
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Thread {
+    /// The id of the thread that the thread belongs to at the top level,
+    /// from the `ThreadAncestor` element.
+    pub ancestor: Option<u64>,
+
+    /// The name of the user who started the thread, from the `ThreadAuthor`
+    /// element.
+    pub author: Option<String>,
+
+    /// Whether and how the thread has been edited, from the
+    /// `ThreadEditStatus` element.
+    pub edit_status: Option<String>,
+
+    /// The id of the thread, from the `ThreadID` element.
+    pub id: Option<u64>,
+
+    /// The title of the talk page that the thread is on, from the
+    /// `ThreadPage` element.
+    pub page: Option<String>,
+
+    /// The id of the thread that the thread is a reply to, from the
+    /// `ThreadParent` element.
+    pub parent: Option<u64>,
+
+    /// The signature of the author, from the `ThreadSignature` element.
+    pub signature: Option<String>,
+
+    /// The subject of the thread, from the `ThreadSubject` element.
+    pub subject: Option<String>,
+
+    /// The type of the thread, such as whether it was moved or deleted,
+    /// from the `ThreadType` element.
+    pub thread_type: Option<String>,
+}
+
+/// Parses the children of a `DiscussionThreading` element after its start
+/// tag, up to and including its end tag. Unknown children are skipped.
+pub(crate) fn parse_thread<R: BufRead, N: FromNamespaceId>(
+    parser: &mut Parser<R, N>,
+    empty: bool,
+) -> Result<Thread, Error> {
+    let mut thread = Thread::default();
+    if empty {
+        return Ok(thread);
+    }
+    loop {
+        let (accepted, event) = next_event(
+            &mut parser.reader,
+            &mut parser.buffer,
+            &mut parser.namespace_buffer,
+            &parser.options,
+        )?;
+        let empty = matches!(event, Event::Empty(_));
+        let name = match event {
+            Event::End(_) => return Ok(thread),
+            Event::Start(event) | Event::Empty(event) if accepted => {
+                event.local_name().to_vec()
+            }
+            Event::Start(_) => {
+                skip_element(parser)?;
+                continue;
+            }
+            Event::Eof => {
+                return Err(Error::UnexpectedEof(
+                    parser.reader.buffer_position(),
+                ))
+            }
+            _ => continue,
+        };
+        macro_rules! text {
+            ($field:ident) => {
+                thread.$field = Some(parse_text(parser, &thread.$field, empty)?)
+            };
+        }
+        macro_rules! id {
+            ($field:ident) => {
+                match parse_text(parser, &thread.$field, empty)?.trim().parse()
+                {
+                    Err(_) => {
+                        return Err(Error::Format(
+                            parser.reader.buffer_position(),
+                        ))
+                    }
+                    Ok(value) => thread.$field = Some(value),
+                }
+            };
+        }
+        match &*name {
+            b"ThreadAncestor" => id!(ancestor),
+            b"ThreadAuthor" => text!(author),
+            b"ThreadEditStatus" => text!(edit_status),
+            b"ThreadID" => id!(id),
+            b"ThreadPage" => text!(page),
+            b"ThreadParent" => id!(parent),
+            b"ThreadSignature" => text!(signature),
+            b"ThreadSubject" => text!(subject),
+            b"ThreadType" => text!(thread_type),
+            _ => {
+                if !empty {
+                    skip_element(parser)?
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{NamespaceId, Page};
+use std::hash::{BuildHasher, Hash, Hasher};
+
+/**
+The case rule of a wiki, as given by the `case` element of the `siteinfo`
+element of a dump.
+
+[`Case::FirstLetter`] is the default of MediaWiki and is used by most
+wikis, except Wiktionaries, which are case-sensitive.
+*/
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Case {
+    /// The first letter of a title is always uppercase, written
+    /// `first-letter` in the dump.
+    FirstLetter,
+    /// Titles are used as they are written, written `case-sensitive` in
+    /// the dump.
+    CaseSensitive,
+}
+
+/**
+Normalizes a title the way MediaWiki does before looking it up.
+
+Underscores are replaced with spaces, runs of whitespace are collapsed into
+a single space, leading and trailing whitespace is removed, and if `case` is
+[`Case::FirstLetter`], the first letter is made uppercase.
+
+The whole title is treated as a page name, so for a title with a namespace
+prefix, only the part after the prefix should be passed. The titles of
+pages in a dump are already normalized, so this is useful mainly for
+matching them against the targets of links.
+
+```rust
+use parse_mediawiki_dump::{normalize_title, Case};
+assert_eq!(normalize_title(" foo__bar ", Case::FirstLetter), "Foo bar");
+assert_eq!(normalize_title("foo_bar", Case::CaseSensitive), "foo bar");
+```
+*/
+pub fn normalize_title(title: &str, case: Case) -> String {
+    let mut output = String::with_capacity(title.len());
+    for word in title
+        .split(|c: char| c == '_' || c.is_whitespace())
+        .filter(|word| !word.is_empty())
+    {
+        if output.is_empty() {
+            let mut chars = word.chars();
+            match (case, chars.next()) {
+                (Case::FirstLetter, Some(first)) => {
+                    output.extend(first.to_uppercase());
+                    output.push_str(chars.as_str());
+                }
+                _ => output.push_str(word),
+            }
+        } else {
+            output.push(' ');
+            output.push_str(word);
+        }
+    }
+    output
+}
+
+/**
+Computes a hash of a namespace and a title with a hasher from `hasher`.
+
+This is the function used for the `title_key` field of pages when enabled
+with [`ParserBuilder::title_key_hasher`](crate::ParserBuilder::title_key_hasher).
+The title is hashed as it is given, including any namespace prefix, so the
+title of a link target must be normalized, for example with
+[`normalize_title`], to get the same key as the page.
+*/
+pub fn title_key(
+    hasher: &impl BuildHasher,
+    namespace: NamespaceId,
+    title: &str,
+) -> u64 {
+    let mut hasher = hasher.build_hasher();
+    namespace.hash(&mut hasher);
+    title.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl Page<NamespaceId> {
+    /**
+    Returns the title of the page normalized with [`normalize_title`].
+
+    For a page outside the main namespace, the title has a namespace
+    prefix, which is kept as it is, and only the page name after it is
+    normalized.
+    */
+    pub fn normalized_title(&self, case: Case) -> String {
+        if self.namespace != NamespaceId(0) {
+            if let Some(index) = self.title.find(':') {
+                let (prefix, name) = self.title.split_at(index + 1);
+                return format!("{}{}", prefix, normalize_title(name, case));
+            }
+        }
+        normalize_title(&self.title, case)
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{
+    schema_version, skip_element, source::next_event, Error, NamespaceId,
+    Parser, ParserBuilder,
+};
+use quick_xml::events::Event;
+use std::io::BufRead;
+
+/// Summary of a dump checked by [`validate`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct ValidationReport {
+    /// The number of `page` elements.
+    pub pages: usize,
+
+    /// The version of the export schema given by the XML namespace of the
+    /// root element, such as `(0, 10)`, if it is the namespace of a version
+    /// of the schema.
+    pub schema_version: Option<(u32, u32)>,
+
+    /// Whether the dump has a `siteinfo` element.
+    pub site_info: bool,
+}
+
+impl ParserBuilder {
+    /**
+    Checks that a stream is a well-formed dump without parsing its pages.
+    See [`validate`].
+    */
+    pub fn validate<R: BufRead>(
+        self,
+        source: R,
+    ) -> Result<ValidationReport, Error> {
+        let mut parser = self.parse(source);
+        let mut report = ValidationReport::default();
+        let empty = loop {
+            let (accepted, event) = read_event(&mut parser)?;
+            let (empty, event) = match event {
+                Event::Start(event) => (false, event),
+                Event::Empty(event) => (true, event),
+                Event::Eof => {
+                    return Err(Error::UnexpectedEof(
+                        parser.reader.buffer_position(),
+                    ))
+                }
+                _ => continue,
+            };
+            if !accepted || event.local_name() != b"mediawiki" {
+                return Err(Error::Format(parser.reader.buffer_position()));
+            }
+            report.schema_version = event
+                .attributes()
+                .filter_map(|r| r.ok())
+                .find(|attribute| attribute.key == b"xmlns")
+                .and_then(|attribute| schema_version(&attribute.value));
+            break empty;
+        };
+        if !empty {
+            loop {
+                let (accepted, event) = read_event(&mut parser)?;
+                match event {
+                    Event::End(_) => break,
+                    Event::Start(event) => {
+                        if accepted {
+                            match event.local_name() {
+                                b"page" => report.pages += 1,
+                                b"siteinfo" => report.site_info = true,
+                                _ => {}
+                            }
+                        }
+                        skip_element(&mut parser)?;
+                    }
+                    Event::Empty(event)
+                        if accepted && event.local_name() == b"page" =>
+                    {
+                        return Err(Error::Format(
+                            parser.reader.buffer_position(),
+                        ));
+                    }
+                    Event::Eof => {
+                        return Err(Error::UnexpectedEof(
+                            parser.reader.buffer_position(),
+                        ))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        // Only the rest of the stream after the root element is read.
+        parser.started = true;
+        parser.ended = true;
+        parser.drain_to_end()?;
+        Ok(report)
+    }
+}
+
+/**
+Checks that a stream is a well-formed dump without parsing its pages,
+returning the number of pages and what the root element tells about the
+dump.
+
+The XML is read to the end with the pages skipped as elements, which is
+faster than parsing them. The root element must be the root element of a
+dump and must be followed only by whitespace, comments and processing
+instructions. Returns the first problem found, with its position, as the
+parser returns it.
+
+```rust
+let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <siteinfo><sitename>Wikipedia</sitename></siteinfo>
+    <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+</mediawiki>"#;
+let report = parse_mediawiki_dump::validate(dump.as_bytes()).unwrap();
+assert_eq!(report.pages, 1);
+assert!(report.site_info);
+assert_eq!(report.schema_version, Some((0, 10)));
+```
+*/
+pub fn validate<R: BufRead>(source: R) -> Result<ValidationReport, Error> {
+    ParserBuilder::new().validate(source)
+}
+
+/// Reads the next event into the buffer of the parser.
+fn read_event<R: BufRead>(
+    parser: &mut Parser<R, NamespaceId>,
+) -> Result<(bool, Event<'_>), Error> {
+    next_event(
+        &mut parser.reader,
+        &mut parser.buffer,
+        &mut parser.namespace_buffer,
+        &parser.options,
+    )
+}
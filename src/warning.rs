@@ -0,0 +1,85 @@
+// Copyright 2018 Fredrik Portström <https://portstrom.com>
+// This is free software distributed under the terms specified in
+// the file LICENSE at the top-level directory of this distribution.
+
+use crate::{Parser, PartialPage};
+use std::io::BufRead;
+
+/**
+A problem in a dump that the parser worked around instead of returning an
+error, recorded if enabled with
+[`ParserBuilder::collect_warnings`](crate::ParserBuilder::collect_warnings).
+
+Each warning has the position in the stream where the problem was noticed.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// A page without an `ns` element was put in the namespace set with
+    /// [`ParserBuilder::default_namespace`](crate::ParserBuilder::default_namespace).
+    DefaultNamespace {
+        /// The title of the page, if the `title` element was parsed.
+        title: Option<String>,
+        /// The position in the stream after the page.
+        position: usize,
+    },
+
+    /// A page with the same namespace and title as a page before it was
+    /// returned, as detected with
+    /// [`ParserBuilder::detect_duplicate_titles`](crate::ParserBuilder::detect_duplicate_titles).
+    DuplicateTitle {
+        /// The title of the page.
+        title: String,
+        /// The position in the stream after the page.
+        position: usize,
+    },
+
+    /// A page containing more than one `revision` element was skipped as
+    /// enabled with
+    /// [`ParserBuilder::skip_unsupported`](crate::ParserBuilder::skip_unsupported).
+    SkippedUnsupported {
+        /// The fields of the page parsed before the second revision.
+        page: PartialPage,
+        /// The position in the stream of the second revision.
+        position: usize,
+    },
+
+    /// The root element is in the XML namespace of a version of the export
+    /// schema not known to this crate, which was accepted as enabled with
+    /// [`ParserBuilder::accept_any_schema`](crate::ParserBuilder::accept_any_schema)
+    /// or by disabling
+    /// [`ParserBuilder::check_xmlns`](crate::ParserBuilder::check_xmlns).
+    UnknownSchema {
+        /// The XML namespace of the root element, empty if it has none.
+        namespace: String,
+        /// The position in the stream after the start tag of the root
+        /// element.
+        position: usize,
+    },
+}
+
+impl<R: BufRead, N> Parser<R, N> {
+    /**
+    Returns the warnings recorded so far, in the order they occurred.
+
+    Always empty unless enabled with
+    [`ParserBuilder::collect_warnings`](crate::ParserBuilder::collect_warnings).
+    Warnings accumulate until taken with [`Parser::take_warnings`].
+    */
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Returns the warnings recorded so far and removes them from the
+    /// parser, so that they don't accumulate while parsing a large dump.
+    pub fn take_warnings(&mut self) -> Vec<Warning> {
+        std::mem::take(&mut self.warnings)
+    }
+}
+
+/// Records a warning if enabled with
+/// [`ParserBuilder::collect_warnings`](crate::ParserBuilder::collect_warnings).
+pub(crate) fn warn<R: BufRead, N>(parser: &mut Parser<R, N>, warning: Warning) {
+    if parser.options.collect_warnings {
+        parser.warnings.push(warning);
+    }
+}
@@ -3,7 +3,10 @@
 // the file LICENSE at the top-level directory of this distribution.
 
 use parse_mediawiki_dump::{impl_namespace, NamespaceId};
-use std::io::{BufReader, Cursor};
+use std::{
+    io::{BufReader, Cursor},
+    sync::Arc,
+};
 
 const DUMP: &str = r#"
 <mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">,
@@ -50,6 +53,7 @@ impl_namespace! {
 }
 
 #[test]
+#[allow(clippy::partialeq_to_none)]
 fn main() {
     let mut parser =
         parse_mediawiki_dump::parse(BufReader::new(Cursor::new(DUMP)));
@@ -61,6 +65,7 @@ fn main() {
             redirect_title,
             text,
             title,
+            ..
         })) =>
             format == "beta"
                 && model == "gamma"
@@ -77,6 +82,7 @@ fn main() {
             redirect_title,
             text,
             title,
+            ..
         })) =>
             redirect_title == Some("zeta".to_string())
                 && text == "eta"
@@ -96,6 +102,7 @@ fn main() {
             redirect_title,
             text,
             title,
+            ..
         })) =>
             format == "beta"
                 && model == "gamma"
@@ -112,6 +119,7 @@ fn main() {
             redirect_title,
             text,
             title,
+            ..
         })) =>
             text == "eta"
                 && title == "epsilon"
@@ -120,3 +128,3777 @@ fn main() {
     });
     assert!(parser.next().is_none());
 }
+
+#[test]
+fn redirect_attributes() {
+    let dump = r#"
+<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <redirect title="beta" kind="gamma &amp; delta" />
+        <revision>
+            <text>epsilon</text>
+        </revision>
+    </page>
+</mediawiki>"#;
+    let page = parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.redirect_title, Some("beta".to_string()));
+    assert_eq!(
+        page.redirect_attributes,
+        vec![("kind".to_string(), "gamma & delta".to_string())]
+    );
+}
+
+#[test]
+fn meta() {
+    let mut parser =
+        parse_mediawiki_dump::parse_meta(BufReader::new(Cursor::new(DUMP)));
+    assert_eq!(
+        parser.next().unwrap().unwrap(),
+        parse_mediawiki_dump::PageMeta {
+            page_id: None,
+            namespace: NamespaceId(0),
+            redirect_title: None,
+            title: "alpha".to_string(),
+        }
+    );
+    assert_eq!(
+        parser.next().unwrap().unwrap(),
+        parse_mediawiki_dump::PageMeta {
+            page_id: None,
+            namespace: NamespaceId(1),
+            redirect_title: Some("zeta".to_string()),
+            title: "epsilon".to_string(),
+        }
+    );
+    assert!(parser.next().is_none());
+
+    let dump = r#"
+<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <title>alpha</title>
+        <ns>0</ns>
+        <id>12</id>
+        <revision>
+            <id>34</id>
+            <text>beta</text>
+        </revision>
+    </page>
+</mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::parse_meta_with_namespace(
+        BufReader::new(Cursor::new(dump)),
+    );
+    assert_eq!(
+        parser.next().unwrap().unwrap(),
+        parse_mediawiki_dump::PageMeta {
+            page_id: Some(12),
+            namespace: Namespace::Main,
+            redirect_title: None,
+            title: "alpha".to_string(),
+        }
+    );
+    assert!(parser.next().is_none());
+}
+
+#[test]
+fn schema_versions() {
+    let dump = |version: &str| {
+        format!(
+            r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-{}/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision>
+            <text>beta</text>
+        </revision>
+    </page>
+</mediawiki>"#,
+            version
+        )
+    };
+    let dump_0_11 = dump("0.11");
+    let mut parser = parse_mediawiki_dump::parse(dump_0_11.as_bytes());
+    assert!(matches!(parser.next(), Some(Ok(page)) if page.title == "alpha"));
+    assert!(parser.next().is_none());
+
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .accept_schema_versions(&[(0, 10)])
+        .parse(dump_0_11.as_bytes());
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::Format(_)))
+    ));
+
+    let dump_1_0 = dump("1.0");
+    let mut parser = parse_mediawiki_dump::parse(dump_1_0.as_bytes());
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::Format(_)))
+    ));
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .accept_any_schema()
+        .parse(dump_1_0.as_bytes());
+    assert!(matches!(parser.next(), Some(Ok(page)) if page.title == "alpha"));
+    assert!(parser.next().is_none());
+}
+
+#[test]
+fn text_spans() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision>
+            <text>beta &amp; gamma</text>
+        </revision>
+    </page>
+    <page>
+        <ns>0</ns>
+        <title>delta</title>
+        <revision>
+            <text />
+        </revision>
+    </page>
+</mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .text_spans(true)
+        .parse(dump.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.text, "");
+    let (start, end) = page.text_span.unwrap();
+    assert_eq!(&dump[start..end], "beta &amp; gamma");
+    let page = parser.next().unwrap().unwrap();
+    let (start, end) = page.text_span.unwrap();
+    assert_eq!(start, end);
+    assert!(parser.next().is_none());
+
+    let page = parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.text, "beta & gamma");
+    assert_eq!(page.text_span, None);
+}
+
+#[test]
+fn partial_page_errors() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision>
+            <text>beta<gamma /></text>
+        </revision>
+    </page>
+</mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::Format(_)))
+    ));
+
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .partial_page_errors(true)
+        .parse(dump.as_bytes());
+    match parser.next() {
+        Some(Err(parse_mediawiki_dump::Error::Page { page, error })) => {
+            assert_eq!(
+                page,
+                parse_mediawiki_dump::PartialPage {
+                    namespace: Some(NamespaceId(0)),
+                    title: Some("alpha".to_string()),
+                }
+            );
+            assert!(matches!(*error, parse_mediawiki_dump::Error::Format(_)));
+        }
+        _ => panic!("expected an error with a partial page"),
+    }
+}
+
+#[test]
+fn try_into_namespace() {
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    let page = page.try_into_namespace::<Namespace>().unwrap();
+    assert_eq!(page.namespace, Namespace::Main);
+    assert_eq!(page.title, "alpha");
+    assert_eq!(page.text, "delta");
+
+    let mut page = parser.next().unwrap().unwrap();
+    page.namespace = NamespaceId(100);
+    assert!(matches!(
+        page.try_into_namespace::<Namespace>(),
+        Err(parse_mediawiki_dump::Error::UnknownNamespace(NamespaceId(
+            100
+        )))
+    ));
+}
+
+#[cfg(feature = "flate2")]
+#[test]
+fn gzip() {
+    use std::io::Write;
+    let mut encoder = flate2::write::GzEncoder::new(
+        Vec::new(),
+        flate2::Compression::default(),
+    );
+    encoder.write_all(DUMP.as_bytes()).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut parser = parse_mediawiki_dump::parse_gz_reader(&compressed[..]);
+    assert!(matches!(parser.next(), Some(Ok(page)) if page.title == "alpha"));
+    assert!(matches!(parser.next(), Some(Ok(page)) if page.title == "epsilon"));
+    assert!(parser.next().is_none());
+
+    let truncated = &compressed[..compressed.len() / 2];
+    let mut parser = parse_mediawiki_dump::parse_gz_reader(truncated);
+    assert!(parser.any(|result| matches!(
+        result,
+        Err(parse_mediawiki_dump::Error::Io { .. })
+    )));
+}
+
+#[test]
+fn text_chunks() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision>
+            <sha1>241vwm8qmjpqzoyp71rcniwl3m8m8ga</sha1>
+            <text>a<![CDATA[<b>]]>c</text>
+        </revision>
+    </page>
+</mediawiki>"#;
+    let page = parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.text, "a<b>c");
+    assert_eq!(
+        page.sha1,
+        Some("241vwm8qmjpqzoyp71rcniwl3m8m8ga".to_string())
+    );
+}
+
+#[cfg(feature = "sha1")]
+#[test]
+fn text_sha1() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision>
+            <sha1>241vwm8qmjpqzoyp71rcniwl3m8m8ga</sha1>
+            <text>a<![CDATA[<b>]]>c</text>
+        </revision>
+    </page>
+</mediawiki>"#;
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .text_sha1(true)
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.text_sha1, page.sha1);
+
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .text_sha1(true)
+        .parse(DUMP.as_bytes());
+    assert_eq!(
+        parser.next().unwrap().unwrap().text_sha1,
+        Some("dhfn5rwpw6yscp08efkmr9otbqvl3lz".to_string())
+    );
+    let page = parse_mediawiki_dump::parse(DUMP.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.text_sha1, None);
+}
+
+#[test]
+fn check_xmlns() {
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .check_xmlns(false)
+        .parse(DUMP.as_bytes());
+    assert!(matches!(parser.next(), Some(Ok(page)) if page.title == "alpha"));
+    assert!(matches!(parser.next(), Some(Ok(page)) if page.title == "epsilon"));
+    assert!(parser.next().is_none());
+
+    let dump = r#"<mediawiki>
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision>
+            <text>beta</text>
+        </revision>
+    </page>
+</mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::Format(_)))
+    ));
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .check_xmlns(false)
+        .parse(dump.as_bytes());
+    assert!(matches!(parser.next(), Some(Ok(page)) if page.text == "beta"));
+    assert!(parser.next().is_none());
+}
+
+#[test]
+fn unsupported() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision>
+            <text>beta</text>
+        </revision>
+        <revision>
+            <text>gamma</text>
+        </revision>
+    </page>
+    <page>
+        <ns>0</ns>
+        <title>delta</title>
+        <revision>
+            <text>epsilon</text>
+        </revision>
+    </page>
+</mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::NotSupported(_)))
+    ));
+
+    let titles = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .on_unsupported({
+            let titles = titles.clone();
+            move |page, _| titles.lock().unwrap().push(page.title.clone())
+        })
+        .skip_unsupported(true)
+        .parse(dump.as_bytes());
+    assert!(matches!(parser.next(), Some(Ok(page)) if page.title == "delta"));
+    assert!(parser.next().is_none());
+    assert_eq!(*titles.lock().unwrap(), vec![Some("alpha".to_string())]);
+}
+
+#[test]
+fn max_namespace_buffer() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page xmlns:a="urn:a" xmlns:b="urn:b" xmlns:c="urn:c">
+        <ns>0</ns>
+        <title>alpha</title>
+        <a:x b:y="z" />
+        <revision>
+            <text>beta</text>
+        </revision>
+    </page>
+    <page>
+        <ns>1</ns>
+        <title>gamma</title>
+        <revision>
+            <text>delta</text>
+        </revision>
+    </page>
+</mediawiki>"#;
+    let titles = parse_mediawiki_dump::ParserBuilder::new()
+        .max_namespace_buffer(0)
+        .parse(dump.as_bytes())
+        .map(|page| page.unwrap().title)
+        .collect::<Vec<_>>();
+    assert_eq!(titles, vec!["alpha", "gamma"]);
+}
+
+#[test]
+fn io_error() {
+    struct Failing<'a>(&'a [u8]);
+
+    impl std::io::Read for Failing<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    "truncated",
+                ));
+            }
+            let length = self.0.len().min(buffer.len());
+            buffer[..length].copy_from_slice(&self.0[..length]);
+            self.0 = &self.0[length..];
+            Ok(length)
+        }
+    }
+
+    let truncated = &DUMP[..DUMP.find("<page>").unwrap() + 6];
+    let mut parser = parse_mediawiki_dump::parse(BufReader::new(Failing(
+        truncated.as_bytes(),
+    )));
+    match parser.next() {
+        Some(Err(error @ parse_mediawiki_dump::Error::Io { .. })) => {
+            assert!(matches!(
+                error,
+                parse_mediawiki_dump::Error::Io { position, .. }
+                    if position == truncated.len()
+            ));
+            let source = std::error::Error::source(&error).unwrap();
+            assert_eq!(source.to_string(), "truncated");
+        }
+        _ => panic!("expected an IO error"),
+    }
+}
+
+#[test]
+fn redirects() {
+    let redirects = parse_mediawiki_dump::resolve_redirects(
+        parse_mediawiki_dump::parse_meta(DUMP.as_bytes()),
+    )
+    .unwrap();
+    assert_eq!(redirects.len(), 1);
+    assert_eq!(redirects["epsilon"], "zeta");
+
+    let redirects = [("a", "b"), ("b", "c"), ("x", "y"), ("y", "x")]
+        .iter()
+        .map(|&(title, target)| (title.to_string(), target.to_string()))
+        .collect();
+    assert_eq!(
+        parse_mediawiki_dump::follow_redirect(&redirects, "a"),
+        Some("c")
+    );
+    assert_eq!(
+        parse_mediawiki_dump::follow_redirect(&redirects, "b"),
+        Some("c")
+    );
+    assert_eq!(parse_mediawiki_dump::follow_redirect(&redirects, "c"), None);
+    assert_eq!(
+        parse_mediawiki_dump::follow_redirect(&redirects, "x"),
+        Some("y")
+    );
+
+    let redirects = (0..100)
+        .map(|i| (i.to_string(), (i + 1).to_string()))
+        .collect();
+    assert_eq!(
+        parse_mediawiki_dump::follow_redirect(&redirects, "0"),
+        Some(
+            parse_mediawiki_dump::MAX_REDIRECT_DEPTH
+                .to_string()
+                .as_str()
+        )
+    );
+}
+
+#[test]
+fn deleted() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision>
+            <contributor deleted="deleted" />
+            <comment>beta</comment>
+            <text>gamma</text>
+        </revision>
+    </page>
+    <page>
+        <ns>0</ns>
+        <title>delta</title>
+        <revision>
+            <contributor><username>epsilon</username></contributor>
+            <comment deleted="deleted" />
+            <text>zeta</text>
+        </revision>
+    </page>
+    <page>
+        <ns>0</ns>
+        <title>eta</title>
+        <revision>
+            <text deleted="deleted" />
+        </revision>
+    </page>
+</mediawiki>"#;
+    let pages = parse_mediawiki_dump::parse(dump.as_bytes())
+        .map(|page| page.unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pages[0].deleted,
+        parse_mediawiki_dump::Deleted {
+            comment: false,
+            contributor: true,
+            text: false,
+        }
+    );
+    assert_eq!(pages[0].text, "gamma");
+    assert_eq!(
+        pages[1].deleted,
+        parse_mediawiki_dump::Deleted {
+            comment: true,
+            contributor: false,
+            text: false,
+        }
+    );
+    assert_eq!(
+        pages[2].deleted,
+        parse_mediawiki_dump::Deleted {
+            comment: false,
+            contributor: false,
+            text: true,
+        }
+    );
+    assert_eq!(pages[2].text, "");
+}
+
+#[test]
+fn reset() {
+    let other = DUMP.replace("alpha", "theta");
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    assert!(matches!(parser.next(), Some(Ok(page)) if page.title == "alpha"));
+    let mut parser = parser.reset(BufReader::new(Cursor::new(other.clone())));
+    assert!(matches!(parser.next(), Some(Ok(page)) if page.title == "theta"));
+    assert!(matches!(parser.next(), Some(Ok(page)) if page.title == "epsilon"));
+    assert!(parser.next().is_none());
+
+    parser.reset_in_place(BufReader::new(Cursor::new(other)));
+    assert_eq!(parser.count(), 2);
+}
+
+#[test]
+fn expand_empty_elements() {
+    let dump = r#"
+<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <redirect title="beta" />
+        <revision>
+            <minor/>
+            <comment deleted="deleted" />
+            <model>wikitext</model>
+            <text deleted="deleted" />
+        </revision>
+    </page>
+    <siteinfo/>
+    <page>
+        <ns>0</ns>
+        <title>gamma</title>
+        <redirect title="delta"></redirect>
+        <revision>
+            <text>epsilon</text>
+        </revision>
+    </page>
+</mediawiki>"#;
+    for &expand in &[true, false] {
+        let pages = parse_mediawiki_dump::ParserBuilder::new()
+            .expand_empty_elements(expand)
+            .parse(dump.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].redirect_title, Some("beta".to_string()));
+        assert_eq!(pages[0].model, Some("wikitext".to_string()));
+        assert_eq!(pages[0].text, "");
+        assert!(pages[0].deleted.comment && pages[0].deleted.text);
+        assert_eq!(pages[1].redirect_title, Some("delta".to_string()));
+        assert_eq!(pages[1].text, "epsilon");
+    }
+}
+
+#[test]
+fn normalize_title() {
+    use parse_mediawiki_dump::{normalize_title, Case};
+    assert_eq!(normalize_title("foo_bar", Case::FirstLetter), "Foo bar");
+    assert_eq!(normalize_title("_foo  _bar_", Case::FirstLetter), "Foo bar");
+    assert_eq!(normalize_title("éte", Case::FirstLetter), "Éte");
+    assert_eq!(normalize_title("foo_bar", Case::CaseSensitive), "foo bar");
+    assert_eq!(normalize_title("", Case::FirstLetter), "");
+
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    let mut page = parser.next().unwrap().unwrap();
+    page.title = "a_b:c".to_string();
+    assert_eq!(page.normalized_title(Case::FirstLetter), "A b:c");
+    page.namespace = NamespaceId(1);
+    page.title = "Talk:foo_bar".to_string();
+    assert_eq!(page.normalized_title(Case::FirstLetter), "Talk:Foo bar");
+    assert_eq!(page.normalized_title(Case::CaseSensitive), "Talk:foo bar");
+}
+
+#[test]
+fn chunks() {
+    let mut chunks = parse_mediawiki_dump::parse(DUMP.as_bytes()).chunks(5);
+    let batch = chunks.next().unwrap().unwrap();
+    assert_eq!(batch.len(), 2);
+    assert_eq!(batch[0].title, "alpha");
+    assert_eq!(batch[1].title, "epsilon");
+    assert!(chunks.next().is_none());
+
+    let dump = DUMP.replace("<ns>1</ns>", "<ns>x</ns>");
+    let mut chunks = parse_mediawiki_dump::parse(dump.as_bytes()).chunks(5);
+    assert_eq!(chunks.next().unwrap().unwrap().len(), 1);
+    assert!(matches!(
+        chunks.next(),
+        Some(Err(parse_mediawiki_dump::Error::InvalidNamespaceId { .. }))
+    ));
+}
+
+#[test]
+fn text_location() {
+    let dump = DUMP.replace(
+        "<text>delta</text>",
+        r#"<text location="store://cluster1/123" />"#,
+    );
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.text, "");
+    assert_eq!(page.text_location, Some("store://cluster1/123".to_string()));
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.text, "eta");
+    assert_eq!(page.text_location, None);
+
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .resolve_text(|location| Ok(format!("fetched {}", location)))
+        .parse(dump.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.text, "fetched store://cluster1/123");
+    assert_eq!(parser.next().unwrap().unwrap().text, "eta");
+
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .resolve_text(|_| {
+            Err(std::io::Error::new(std::io::ErrorKind::NotFound, "theta"))
+        })
+        .parse(dump.as_bytes());
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::Io { .. }))
+    ));
+}
+
+#[test]
+fn namespace_default() {
+    impl_namespace! {
+        enum DefaultNamespace {
+            Talk = 1,
+            #[default]
+            Main = 0,
+            User = 2,
+        }
+    }
+    assert_eq!(DefaultNamespace::default(), DefaultNamespace::Main);
+    let page = parse_mediawiki_dump::Page::<DefaultNamespace>::default();
+    assert_eq!(page.namespace, DefaultNamespace::Main);
+    assert_eq!(page.title, "");
+
+    let mut parser = parse_mediawiki_dump::parse_with_namespace::<
+        _,
+        DefaultNamespace,
+    >(DUMP.as_bytes());
+    assert_eq!(
+        parser.next().unwrap().unwrap().namespace,
+        DefaultNamespace::Main
+    );
+    assert_eq!(
+        parser.next().unwrap().unwrap().namespace,
+        DefaultNamespace::Talk
+    );
+}
+
+#[test]
+fn shared_position() {
+    use std::sync::atomic::Ordering;
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    let position = parser.shared_position();
+    assert_eq!(position.load(Ordering::Relaxed), 0);
+    parser.next().unwrap().unwrap();
+    let first = DUMP.find("</page>").unwrap() + "</page>".len();
+    assert_eq!(position.load(Ordering::Relaxed), first);
+    parser.next().unwrap().unwrap();
+    let second = DUMP.rfind("</page>").unwrap() + "</page>".len();
+    assert_eq!(position.load(Ordering::Relaxed), second);
+    assert!(parser.next().is_none());
+    assert_eq!(position.load(Ordering::Relaxed), DUMP.len());
+    assert!(Arc::ptr_eq(&position, &parser.shared_position()));
+}
+
+/// Calls `f` with every permutation of `items`.
+fn permutations<'a>(
+    items: &mut [&'a str],
+    k: usize,
+    f: &mut impl FnMut(&[&'a str]),
+) {
+    if k == items.len() {
+        f(items);
+    }
+    for i in k..items.len() {
+        items.swap(k, i);
+        permutations(items, k + 1, f);
+        items.swap(k, i);
+    }
+}
+
+#[test]
+fn page_child_order() {
+    let mut count = 0;
+    permutations(
+        &mut [
+            "<ns>1</ns>",
+            "<title>alpha</title>",
+            r#"<redirect title="beta" />"#,
+            "<revision><text>gamma</text></revision>",
+        ],
+        0,
+        &mut |children| {
+            let dump = format!(
+                r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+                    <page>{}</page>
+                </mediawiki>"#,
+                children.concat()
+            );
+            let page =
+                parse_mediawiki_dump::parse_with_namespace::<_, Namespace>(
+                    dump.as_bytes(),
+                )
+                .next()
+                .unwrap()
+                .unwrap();
+            assert_eq!(page.namespace, Namespace::Talk);
+            assert_eq!(page.title, "alpha");
+            assert_eq!(page.redirect_title, Some("beta".to_string()));
+            assert_eq!(page.text, "gamma");
+            count += 1;
+        },
+    );
+    assert_eq!(count, 24);
+}
+
+#[test]
+fn revision_child_order() {
+    let mut count = 0;
+    permutations(
+        &mut [
+            "<format>alpha</format>",
+            "<model>beta</model>",
+            "<sha1>gamma</sha1>",
+            "<text>delta</text>",
+            "<comment>epsilon</comment>",
+        ],
+        0,
+        &mut |children| {
+            let dump = format!(
+                r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+                    <page>
+                        <ns>0</ns>
+                        <title>zeta</title>
+                        <revision>{}</revision>
+                    </page>
+                </mediawiki>"#,
+                children.concat()
+            );
+            let page = parse_mediawiki_dump::parse(dump.as_bytes())
+                .next()
+                .unwrap()
+                .unwrap();
+            assert_eq!(page.format, Some("alpha".to_string()));
+            assert_eq!(page.model, Some("beta".to_string()));
+            assert_eq!(page.sha1, Some("gamma".to_string()));
+            assert_eq!(page.text, "delta");
+            count += 1;
+        },
+    );
+    assert_eq!(count, 120);
+}
+
+#[test]
+fn fixture_child_order() {
+    // The fixture has `title` after `ns` in the first page and before it in
+    // the second.
+    assert!(DUMP.find("<ns>0</ns>") < DUMP.find("<title>alpha</title>"));
+    assert!(DUMP.find("<title>epsilon</title>") < DUMP.find("<ns>1</ns>"));
+    let pages = parse_mediawiki_dump::parse_with_namespace::<_, Namespace>(
+        DUMP.as_bytes(),
+    )
+    .map(|page| page.map(|page| (page.namespace, page.title)))
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+    assert_eq!(
+        pages,
+        [
+            (Namespace::Main, "alpha".to_string()),
+            (Namespace::Talk, "epsilon".to_string())
+        ]
+    );
+}
+
+#[test]
+fn text_hint() {
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .text_hint(1000)
+        .parse(DUMP.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.text, "delta");
+    assert!(page.text.capacity() >= 1000);
+    assert!(page.title.capacity() < 1000);
+
+    let dump = DUMP.replace("<text>eta</text>", "<text/>");
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .text_hint(1000)
+        .parse(dump.as_bytes());
+    parser.next().unwrap().unwrap();
+    assert_eq!(parser.next().unwrap().unwrap().text.capacity(), 0);
+}
+
+#[test]
+fn enumerate_offsets() {
+    let expected = DUMP
+        .match_indices("<page>")
+        .map(|(offset, _)| offset)
+        .collect::<Vec<_>>();
+    let offsets = parse_mediawiki_dump::parse(DUMP.as_bytes())
+        .enumerate_offsets()
+        .map(|item| item.map(|(offset, _)| offset))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(offsets, expected);
+    assert!(offsets.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let dump = DUMP.replacen(
+        "<page>",
+        "<siteinfo><sitename>theta</sitename></siteinfo>\n    <page>",
+        1,
+    );
+    let mut parser =
+        parse_mediawiki_dump::parse(dump.as_bytes()).enumerate_offsets();
+    let (offset, page) = parser.next().unwrap().unwrap();
+    assert_eq!(offset, dump.find("<page>").unwrap());
+    assert_eq!(page.title, "alpha");
+    let (offset, page) = parser.next().unwrap().unwrap();
+    assert_eq!(offset, dump.rfind("<page>").unwrap());
+    assert_eq!(page.title, "epsilon");
+}
+
+#[test]
+fn borrowed_reader() {
+    use std::io::Read;
+    let mut reader = BufReader::new(Cursor::new(DUMP));
+    let mut parser = parse_mediawiki_dump::parse(&mut reader);
+    assert_eq!(parser.next().unwrap().unwrap().title, "alpha");
+    let reader = parser.into_inner();
+    let mut rest = String::new();
+    reader.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, &DUMP[DUMP.find("</page>").unwrap() + 7..]);
+
+    let mut reader = BufReader::new(Cursor::new(DUMP));
+    let mut parser = parse_mediawiki_dump::parse(&mut reader);
+    assert_eq!(parser.by_ref().count(), 2);
+    let mut other = DUMP.as_bytes();
+    let parser = parser.reset(&mut other);
+    assert_eq!(parser.chunks(1).count(), 2);
+    let mut reader = BufReader::new(Cursor::new(DUMP));
+    let parser = parse_mediawiki_dump::parse(&mut reader);
+    assert_eq!(parser.enumerate_offsets().count(), 2);
+    let mut rest = String::new();
+    reader.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "");
+}
+
+#[test]
+fn normalize_model() {
+    let dump = DUMP
+        .replace("<model>gamma</model>", "<model> Wikitext </model>")
+        .replace("<format>beta</format>", "<format>Text/X-Wiki\n</format>");
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .normalize_model(true)
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.model, Some("wikitext".to_string()));
+    assert_eq!(page.raw_model, Some(" Wikitext ".to_string()));
+    assert_eq!(page.format, Some("text/x-wiki".to_string()));
+    assert_eq!(page.raw_format, Some("Text/X-Wiki\n".to_string()));
+
+    let page = parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.model, Some(" Wikitext ".to_string()));
+    assert_eq!(page.raw_model, None);
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn parse_into_channel() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let titles = runtime.block_on(async {
+        let mut receiver = parse_mediawiki_dump::parse_into_channel::<
+            _,
+            Namespace,
+        >(DUMP.as_bytes(), 1);
+        let mut titles = vec![];
+        while let Some(page) = receiver.recv().await {
+            let page = page.unwrap();
+            titles.push((page.namespace, page.title));
+        }
+        titles
+    });
+    assert_eq!(
+        titles,
+        [
+            (Namespace::Main, "alpha".to_string()),
+            (Namespace::Talk, "epsilon".to_string())
+        ]
+    );
+}
+
+#[test]
+fn is_content_article() {
+    let dump = DUMP
+        .replace("<format>beta</format>", "<format>text/x-wiki</format>")
+        .replace("<model>gamma</model>", "<model>wikitext</model>");
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    let mut page = parser.next().unwrap().unwrap();
+    assert!(page.is_content_article());
+    assert!(!parser.next().unwrap().unwrap().is_content_article());
+    page.namespace = NamespaceId(1);
+    assert!(!page.is_content_article());
+    assert!(!parse_mediawiki_dump::parse(DUMP.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap()
+        .is_content_article());
+
+    let mut parser = parse_mediawiki_dump::parse_with_namespace::<_, Namespace>(
+        dump.as_bytes(),
+    );
+    let page = parser.next().unwrap().unwrap();
+    assert!(page.is_content_article_in(&Namespace::Main));
+    assert!(!page.is_content_article_in(&Namespace::Talk));
+}
+
+#[test]
+fn chained() {
+    let first = DUMP.replacen(
+        "<page>",
+        "<siteinfo><sitename>theta</sitename></siteinfo>\n    <page>",
+        1,
+    );
+    let second = DUMP.replace("alpha", "iota").replace("epsilon", "kappa");
+    let titles = parse_mediawiki_dump::parse_chained(vec![
+        first.as_bytes(),
+        "".as_bytes(),
+        second.as_bytes(),
+    ])
+    .map(|page| page.map(|page| page.title))
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+    assert_eq!(titles, ["alpha", "epsilon", "iota", "kappa"]);
+
+    let concatenated = format!("{}\n{}", DUMP, DUMP);
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .multiple_roots(true)
+        .parse(concatenated.as_bytes());
+    assert_eq!(parser.by_ref().count(), 4);
+    assert!(parser.next().is_none());
+
+    let mut parser = parse_mediawiki_dump::parse(concatenated.as_bytes());
+    assert_eq!(parser.by_ref().count(), 2);
+}
+
+#[test]
+fn by_ref() {
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    let titles = (&mut parser)
+        .take(1)
+        .map(|page| page.unwrap().title)
+        .collect::<Vec<_>>();
+    assert_eq!(titles, ["alpha"]);
+    assert_eq!(parser.next().unwrap().unwrap().title, "epsilon");
+    assert!(parser.next().is_none());
+
+    let mut parser = parse_mediawiki_dump::parse_meta(DUMP.as_bytes());
+    assert_eq!(parser.by_ref().take(1).count(), 1);
+    assert_eq!(parser.next().unwrap().unwrap().title, "epsilon");
+}
+
+#[test]
+fn strip_text_bom() {
+    let dump = DUMP
+        .replace("<text>delta</text>", "<text>\u{feff}delta</text>")
+        .replace("<title>alpha</title>", "<title>\u{feff}alpha</title>");
+    let page = parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.text, "\u{feff}delta");
+    assert_eq!(page.title, "\u{feff}alpha");
+
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .strip_text_bom(true)
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.text, "delta");
+    assert_eq!(page.title, "alpha");
+}
+
+#[test]
+fn invalid_namespace_id() {
+    let dump = DUMP.replace("<ns>0</ns>", "<ns>99999999999</ns>");
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    match parser.next() {
+        Some(Err(parse_mediawiki_dump::Error::InvalidNamespaceId {
+            value,
+            position,
+        })) => {
+            assert_eq!(value, "99999999999");
+            assert_eq!(
+                position,
+                dump.find("99999999999</ns>").unwrap()
+                    + "99999999999</ns>".len()
+            );
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn default_namespace() {
+    let dump = DUMP.replace("<ns>1</ns>", "");
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    parser.next().unwrap().unwrap();
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::Format(_)))
+    ));
+
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .default_namespace(NamespaceId(0))
+        .parse_with_namespace::<_, Namespace>(dump.as_bytes());
+    assert_eq!(parser.next().unwrap().unwrap().namespace, Namespace::Main);
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.namespace, Namespace::Main);
+    assert_eq!(page.title, "epsilon");
+
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .default_namespace(NamespaceId(100))
+        .parse_meta_with_namespace::<_, Namespace>(dump.as_bytes());
+    parser.next().unwrap().unwrap();
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::Namespace {
+            id: NamespaceId(100),
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn identity() {
+    let pages = parse_mediawiki_dump::parse_with_namespace::<_, Namespace>(
+        DUMP.as_bytes(),
+    )
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+    let lengths = pages
+        .iter()
+        .map(|page| (page.identity(), page.text.len()))
+        .collect::<std::collections::HashMap<_, _>>();
+    assert_eq!(lengths.len(), 2);
+    assert_eq!(lengths[&(Namespace::Main, "alpha")], 5);
+    assert_eq!(lengths[&(Namespace::Talk, "epsilon")], 3);
+    assert!(!lengths.contains_key(&(Namespace::Talk, "alpha")));
+}
+
+#[test]
+fn declared_text_attributes() {
+    let dump = DUMP.replace(
+        "<text>delta</text>",
+        r#"<text bytes="5" sha1="kv3lrwbrsgbcvqf5rs3kzpfv8jm5f5p" xml:space="preserve">delta</text>"#,
+    );
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.text, "delta");
+    assert_eq!(page.declared_bytes, Some(5));
+    assert_eq!(
+        page.declared_sha1,
+        Some("kv3lrwbrsgbcvqf5rs3kzpfv8jm5f5p".to_string())
+    );
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.declared_bytes, None);
+    assert_eq!(page.declared_sha1, None);
+
+    let dump = DUMP.replace("<text>", r#"<text bytes="x">"#);
+    assert!(matches!(
+        parse_mediawiki_dump::parse(dump.as_bytes()).next(),
+        Some(Err(parse_mediawiki_dump::Error::Format(_)))
+    ));
+}
+
+#[test]
+fn skip_empty_elements() {
+    let dump = r#"
+<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <siteinfo><namespaces><namespace key="0" /></namespaces></siteinfo>
+    <page>
+        <ns>0</ns>
+        <unknown><empty/><nested><empty /></nested></unknown>
+        <title>alpha</title>
+        <revision>
+            <contributor><username>beta</username><id/></contributor>
+            <text>gamma</text>
+        </revision>
+    </page>
+    <page>
+        <ns>0</ns>
+        <title>delta</title>
+        <revision><text>epsilon</text></revision>
+    </page>
+</mediawiki>"#;
+    for &expand in &[true, false] {
+        let titles = parse_mediawiki_dump::ParserBuilder::new()
+            .expand_empty_elements(expand)
+            .parse(dump.as_bytes())
+            .map(|page| page.map(|page| (page.title, page.text)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            titles,
+            [
+                ("alpha".to_string(), "gamma".to_string()),
+                ("delta".to_string(), "epsilon".to_string())
+            ]
+        );
+    }
+
+    let truncated = &dump[..dump.find("<title>alpha").unwrap()];
+    let truncated = truncated.replace("</unknown>", "");
+    assert!(matches!(
+        parse_mediawiki_dump::parse(truncated.as_bytes()).next(),
+        Some(Err(parse_mediawiki_dump::Error::UnexpectedEof(_)))
+    ));
+}
+
+#[test]
+fn revisions_flat() {
+    let dump = r#"
+<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <id>1</id>
+        <revision><id>2</id><text>beta</text></revision>
+        <revision><id>3</id><model>gamma</model><text>delta</text></revision>
+    </page>
+    <page>
+        <ns>1</ns>
+        <title>epsilon</title>
+        <redirect title="zeta" />
+        <revision><text>eta</text></revision>
+    </page>
+</mediawiki>"#;
+    let revisions = parse_mediawiki_dump::parse_revisions_flat(dump.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(revisions.len(), 3);
+    for (page, _) in &revisions[..2] {
+        assert_eq!(page.namespace, parse_mediawiki_dump::NamespaceId(0));
+        assert_eq!(page.page_id, Some(1));
+        assert_eq!(page.title, "alpha");
+    }
+    assert_eq!(revisions[0].1.model, None);
+    assert_eq!(revisions[0].1.text, "beta");
+    assert_eq!(revisions[1].1.model, Some("gamma".to_string()));
+    assert_eq!(revisions[1].1.text, "delta");
+    let (page, revision) = &revisions[2];
+    assert_eq!(page.namespace, parse_mediawiki_dump::NamespaceId(1));
+    assert_eq!(page.redirect_title, Some("zeta".to_string()));
+    assert_eq!(page.title, "epsilon");
+    assert_eq!(revision.text, "eta");
+
+    let dump = DUMP.replace("<ns>0</ns>", "");
+    assert!(matches!(
+        parse_mediawiki_dump::parse_revisions_flat(dump.as_bytes()).next(),
+        Some(Err(parse_mediawiki_dump::Error::Format(_)))
+    ));
+}
+
+#[test]
+fn max_revisions_per_page() {
+    let dump = r#"
+<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision><text>beta</text></revision>
+        <revision><text>gamma</text></revision>
+    </page>
+    <page>
+        <ns>0</ns>
+        <title>delta</title>
+        <revision><text>epsilon</text></revision>
+    </page>
+</mediawiki>"#;
+    let mut revisions = parse_mediawiki_dump::ParserBuilder::new()
+        .max_revisions_per_page(1)
+        .parse(dump.as_bytes())
+        .revisions_flat()
+        .map(|item| item.map(|(page, revision)| (page.title, revision.text)));
+    assert_eq!(
+        revisions.next().unwrap().unwrap(),
+        ("alpha".to_string(), "beta".to_string())
+    );
+    assert!(matches!(
+        revisions.next(),
+        Some(Err(parse_mediawiki_dump::Error::TooManyRevisions {
+            limit: 1,
+            ..
+        }))
+    ));
+    assert_eq!(
+        revisions.next().unwrap().unwrap(),
+        ("delta".to_string(), "epsilon".to_string())
+    );
+    assert!(revisions.next().is_none());
+}
+
+#[test]
+fn raw_namespace() {
+    let dump = DUMP.replace("<ns>0</ns>", "<ns> 0 </ns>");
+    let page = parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.namespace, NamespaceId(0));
+    assert_eq!(page.raw_namespace, None);
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .raw_namespace(true)
+        .parse(dump.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.namespace, NamespaceId(0));
+    assert_eq!(page.raw_namespace, Some(" 0 ".to_string()));
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.raw_namespace, Some("1".to_string()));
+}
+
+#[test]
+fn padded_namespace() {
+    let dump = DUMP.replace("<ns>0</ns>", "<ns>\n\t 0\n</ns>");
+    let page = parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.namespace, NamespaceId(0));
+    // Only whitespace around the id is ignored.
+    let dump = DUMP.replace("<ns>0</ns>", "<ns> 0 1 </ns>");
+    assert!(matches!(
+        parse_mediawiki_dump::parse(dump.as_bytes()).next(),
+        Some(Err(parse_mediawiki_dump::Error::InvalidNamespaceId { value, .. }))
+            if value == " 0 1 "
+    ));
+}
+
+#[test]
+fn send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<parse_mediawiki_dump::Parser<&[u8], NamespaceId>>();
+    assert_send::<parse_mediawiki_dump::Parser<&[u8], std::rc::Rc<i32>>>();
+    assert_send::<parse_mediawiki_dump::MetaParser<&[u8], NamespaceId>>();
+    assert_send::<parse_mediawiki_dump::RevisionsFlat<&[u8], NamespaceId>>();
+    assert_send::<parse_mediawiki_dump::Chunks<&[u8], NamespaceId>>();
+    let parser = parse_mediawiki_dump::ParserBuilder::new()
+        .on_unsupported(|_, _| {})
+        .resolve_text(|location| Ok(location.to_string()))
+        .parse(DUMP.as_bytes());
+    let pages = std::thread::spawn(move || parser.count());
+    assert_eq!(pages.join().unwrap(), 2);
+}
+
+#[test]
+fn clear() {
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    parser.next().unwrap().unwrap();
+    let mut page = parser.next().unwrap().unwrap();
+    let text_capacity = page.text.capacity();
+    let title_capacity = page.title.capacity();
+    page.clear();
+    assert_eq!(page.namespace, NamespaceId(1));
+    assert_eq!(page.redirect_title, None);
+    assert_eq!(page.text, "");
+    assert_eq!(page.text.capacity(), text_capacity);
+    assert_eq!(page.title, "");
+    assert_eq!(page.title.capacity(), title_capacity);
+}
+
+#[test]
+fn namespace_range() {
+    let dump = r#"
+<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+    <page><ns>1</ns><title>Talk:beta</title><revision><text/></revision></page>
+    <page><title>User:gamma</title><ns>2</ns><revision><text/></revision></page>
+    <page><ns>3</ns><title>User talk:delta</title><revision><text/></revision></page>
+    <page><ns>x</ns>"#;
+    let range = NamespaceId(1)..=NamespaceId(2);
+    let titles = parse_mediawiki_dump::ParserBuilder::new()
+        .namespace_range(range.clone())
+        .parse(dump.as_bytes())
+        .map(|page| page.map(|page| page.title))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(titles, ["Talk:beta", "User:gamma"]);
+
+    let titles = parse_mediawiki_dump::ParserBuilder::new()
+        .namespace_range(range.clone())
+        .parse_meta(dump.as_bytes())
+        .map(|page| page.map(|page| page.title))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(titles, ["Talk:beta", "User:gamma"]);
+
+    let titles = parse_mediawiki_dump::ParserBuilder::new()
+        .namespace_range(range)
+        .parse(dump.as_bytes())
+        .revisions_flat()
+        .map(|item| item.map(|(page, _)| page.title))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(titles, ["Talk:beta", "User:gamma"]);
+}
+
+#[test]
+fn content_model() {
+    use parse_mediawiki_dump::{ContentFormat, ContentModel};
+    for &model in &[
+        "css",
+        "flow-board",
+        "javascript",
+        "json",
+        "proofread-page",
+        "sanitized-css",
+        "Scribunto",
+        "text",
+        "wikibase-item",
+        "wikibase-lexeme",
+        "wikibase-property",
+        "wikitext",
+        "unknown",
+    ] {
+        let parsed = model.parse::<ContentModel>().unwrap();
+        assert_eq!(parsed.to_string(), model);
+        assert_eq!(parsed.as_str().parse(), Ok(parsed.clone()));
+        assert_eq!(
+            matches!(parsed, ContentModel::Other(_)),
+            model == "unknown"
+        );
+    }
+    for &format in &[
+        "application/json",
+        "application/vnd.php.serialized",
+        "application/xml",
+        "text/css",
+        "text/html",
+        "text/javascript",
+        "text/plain",
+        "text/x-wiki",
+        "text/unknown",
+    ] {
+        let parsed = format.parse::<ContentFormat>().unwrap();
+        assert_eq!(parsed.to_string(), format);
+        assert_eq!(
+            matches!(parsed, ContentFormat::Other(_)),
+            format == "text/unknown"
+        );
+    }
+
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(
+        page.content_model(),
+        Some(ContentModel::Other("gamma".to_string()))
+    );
+    assert_eq!(
+        page.content_format(),
+        Some(ContentFormat::Other("beta".to_string()))
+    );
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.content_model(), None);
+}
+
+#[test]
+fn lenient_text() {
+    let dump = DUMP.replace(
+        "<text>delta</text>",
+        "<text>a<b>c<i x=\"y\">d</i><!--e-->f</text>",
+    );
+    assert!(parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .is_err());
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .lenient_text(true)
+        .parse(dump.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.text, "a<b>c<i x=\"y\">d</i><!--e-->f");
+    assert_eq!(page.title, "alpha");
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.text, "eta");
+    assert!(parser.next().is_none());
+}
+
+#[test]
+fn error_display_and_source() {
+    use std::error::Error as _;
+    let dump = DUMP.replace("<ns>0</ns>", "<ns>x</ns>");
+    let error = parse_mediawiki_dump::ParserBuilder::new()
+        .partial_page_errors(true)
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap_err();
+    let position = dump.find("x</ns>").unwrap() + "x</ns>".len();
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "The namespace id \"x\" at position {} is invalid in page with \
+             unknown title",
+            position
+        )
+    );
+    assert!(matches!(
+        error
+            .source()
+            .unwrap()
+            .downcast_ref::<Box<parse_mediawiki_dump::Error>>()
+            .map(|error| &**error),
+        Some(parse_mediawiki_dump::Error::InvalidNamespaceId { .. })
+    ));
+
+    let dump = DUMP.replace("</title>", "</titl>");
+    let error = parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap_err();
+    let source = error.source().unwrap();
+    assert!(source.is::<quick_xml::Error>());
+    assert_eq!(error.to_string(), source.to_string());
+}
+
+#[test]
+fn capture_unknown_page_fields() {
+    let dump = DUMP.replace(
+        "<title>alpha</title>",
+        "<title>alpha</title><sha1>beta</sha1>\
+         <discussionthreadinfo><id>1</id></discussionthreadinfo>",
+    );
+    let page = parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert!(page.extra.is_empty());
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .capture_unknown_page_fields(true)
+        .parse(dump.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.extra.len(), 2);
+    assert_eq!(page.extra["sha1"], "beta");
+    assert_eq!(page.extra["discussionthreadinfo"], "<id>1</id>");
+    assert_eq!(page.text, "delta");
+    assert!(parser.next().unwrap().unwrap().extra.is_empty());
+}
+
+#[test]
+fn drain_to_end() {
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    assert_eq!(parser.next().unwrap().unwrap().title, "alpha");
+    parser.drain_to_end().unwrap();
+    assert!(parser.next().is_none());
+    parser.drain_to_end().unwrap();
+
+    let dump = format!("{}<!-- comment -->\n", DUMP);
+    parse_mediawiki_dump::parse(dump.as_bytes())
+        .drain_to_end()
+        .unwrap();
+    let dump = format!("{}<page/>", DUMP);
+    assert!(matches!(
+        parse_mediawiki_dump::parse(dump.as_bytes()).drain_to_end(),
+        Err(parse_mediawiki_dump::Error::Format(_))
+    ));
+
+    for &end in &["<page>", "</revision>", "</page>", "</mediawiki>"] {
+        let truncated = &DUMP[..DUMP.rfind(end).unwrap()];
+        let mut parser = parse_mediawiki_dump::parse(truncated.as_bytes());
+        assert_eq!(parser.next().unwrap().unwrap().title, "alpha");
+        assert!(matches!(
+            parser.drain_to_end(),
+            Err(parse_mediawiki_dump::Error::UnexpectedEof(position))
+                if position == truncated.len()
+        ));
+    }
+    let truncated = &DUMP[..DUMP.rfind("</mediawiki>").unwrap()];
+    let mut parser = parse_mediawiki_dump::parse(truncated.as_bytes());
+    assert_eq!(parser.by_ref().filter_map(Result::ok).count(), 2);
+    assert!(parser.next().is_none());
+}
+
+#[test]
+fn dump_order() {
+    use parse_mediawiki_dump::DumpOrd;
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    let alpha = parser.next().unwrap().unwrap();
+    let epsilon = parser.next().unwrap().unwrap();
+    let talk = parse_mediawiki_dump::Page {
+        title: "Talk:A".to_string(),
+        ..epsilon.clone()
+    };
+    assert!(alpha.cmp_dump_order(&epsilon).is_lt());
+    assert!(talk.cmp_dump_order(&alpha).is_gt());
+    let mut pages = [
+        DumpOrd(epsilon.clone()),
+        DumpOrd(alpha.clone()),
+        DumpOrd(talk),
+    ];
+    pages.sort();
+    let titles = pages
+        .iter()
+        .map(|page| page.0.title.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(titles, ["alpha", "Talk:A", "epsilon"]);
+
+    let page = |title: &str| {
+        DumpOrd(parse_mediawiki_dump::Page {
+            title: title.to_string(),
+            ..alpha.clone()
+        })
+    };
+    assert!(page("A b") > page("A!"));
+    assert!(page("A b") == page("A_b"));
+}
+
+#[test]
+fn text_transform() {
+    use parse_mediawiki_dump::Field;
+    let fields = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .text_transform({
+            let fields = fields.clone();
+            move |field, value| {
+                fields.lock().unwrap().push(field);
+                match field {
+                    Field::Title | Field::RedirectTitle => value.to_uppercase(),
+                    _ => value,
+                }
+            }
+        })
+        .parse(DUMP.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.title, "ALPHA");
+    assert_eq!(page.text, "delta");
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.title, "EPSILON");
+    assert_eq!(page.redirect_title, Some("ZETA".to_string()));
+    assert_eq!(page.text, "eta");
+    let mut fields = fields.lock().unwrap().clone();
+    fields.sort_by_key(|field| format!("{:?}", field));
+    assert_eq!(
+        fields,
+        [
+            Field::Format,
+            Field::Model,
+            Field::RedirectTitle,
+            Field::Text,
+            Field::Text,
+            Field::Title,
+            Field::Title,
+        ]
+    );
+}
+
+#[test]
+fn items() {
+    use parse_mediawiki_dump::Item;
+    let ending = |dump: &str| {
+        let mut items = parse_mediawiki_dump::parse_items(dump.as_bytes());
+        let mut pages = 0;
+        let mut errors = 0;
+        loop {
+            match items.next().unwrap() {
+                Err(_) => errors += 1,
+                Ok(Item::Page(_)) => pages += 1,
+                Ok(Item::End { clean }) => {
+                    assert!(items.next().is_none());
+                    return (pages, errors, clean);
+                }
+            }
+        }
+    };
+    assert_eq!(ending(DUMP), (2, 0, true));
+    assert_eq!(ending(&format!("{}<!-- comment -->", DUMP)), (2, 0, true));
+    assert_eq!(ending(&format!("{}<mediawiki>", DUMP)), (2, 0, false));
+    let truncated = &DUMP[..DUMP.rfind("</mediawiki>").unwrap()];
+    assert_eq!(ending(truncated), (2, 1, false));
+    let truncated = &DUMP[..DUMP.rfind("</page>").unwrap()];
+    assert_eq!(ending(truncated), (1, 1, false));
+
+    // The end of the stream within a page is wrapped in `Error::Page`.
+    let mut items = parse_mediawiki_dump::ParserBuilder::new()
+        .partial_page_errors(true)
+        .parse(truncated.as_bytes())
+        .items();
+    assert!(matches!(items.next(), Some(Ok(Item::Page(_)))));
+    assert!(matches!(
+        items.next(),
+        Some(Err(parse_mediawiki_dump::Error::Page { .. }))
+    ));
+    assert!(matches!(items.next(), Some(Ok(Item::End { clean: false }))));
+    assert!(items.next().is_none());
+}
+
+#[test]
+fn title_key() {
+    use std::{collections::hash_map::DefaultHasher, hash::BuildHasherDefault};
+    let keys = || {
+        parse_mediawiki_dump::ParserBuilder::new()
+            .title_key_hasher(BuildHasherDefault::<DefaultHasher>::default())
+            .parse(DUMP.as_bytes())
+            .map(|page| page.unwrap().title_key.unwrap())
+            .collect::<Vec<_>>()
+    };
+    let first = keys();
+    assert_eq!(first, keys());
+    assert_ne!(first[0], first[1]);
+    let hasher = BuildHasherDefault::<DefaultHasher>::default();
+    assert_eq!(
+        first,
+        [
+            parse_mediawiki_dump::title_key(&hasher, NamespaceId(0), "alpha"),
+            parse_mediawiki_dump::title_key(&hasher, NamespaceId(1), "epsilon"),
+        ]
+    );
+    assert_ne!(
+        first[0],
+        parse_mediawiki_dump::title_key(&hasher, NamespaceId(1), "alpha")
+    );
+    let page = parse_mediawiki_dump::parse(DUMP.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.title_key, None);
+}
+
+#[test]
+fn parse_from_offset() {
+    let offsets = parse_mediawiki_dump::parse(DUMP.as_bytes())
+        .enumerate_offsets()
+        .map(|page| page.unwrap().0)
+        .collect::<Vec<_>>();
+    let mut parser = parse_mediawiki_dump::parse_from_offset(
+        Cursor::new(DUMP),
+        offsets[1] as u64,
+    )
+    .unwrap();
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.title, "epsilon");
+    assert_eq!(page.text, "eta");
+    assert!(parser.next().is_none());
+
+    let mut parser = parse_mediawiki_dump::parse_from_offset(
+        BufReader::with_capacity(16, Cursor::new(DUMP)),
+        offsets[0] as u64,
+    )
+    .unwrap();
+    assert_eq!(parser.by_ref().count(), 2);
+
+    for offset in &[offsets[1] + 1, offsets[1] - 1, 0] {
+        assert!(matches!(
+            parse_mediawiki_dump::parse_from_offset(
+                Cursor::new(DUMP),
+                *offset as u64
+            ),
+            Err(parse_mediawiki_dump::Error::Format(0))
+        ));
+    }
+}
+
+#[cfg(feature = "memchr")]
+#[test]
+fn parse_skipping_siteinfo() {
+    for page in &["<page>", "<page\n    >"] {
+        let siteinfo = "<siteinfo><sitename>Wiki</sitename><namespaces>\
+            <namespace key=\"0\" /><namespace key=\"1\">Talk</namespace>\
+            </namespaces><pages /></siteinfo>";
+        let dump = DUMP.replacen("<page>", &format!("{}{}", siteinfo, page), 1);
+        // Small buffers make the scan stop within tags.
+        for capacity in 1..64 {
+            let titles = parse_mediawiki_dump::parse_skipping_siteinfo(
+                BufReader::with_capacity(capacity, dump.as_bytes()),
+            )
+            .unwrap()
+            .map(|page| page.map(|page| page.title))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+            assert_eq!(titles, ["alpha", "epsilon"], "capacity {}", capacity);
+        }
+    }
+
+    let mut parser = parse_mediawiki_dump::parse_skipping_siteinfo(
+        r#"<?xml version="1.0"?><mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/" />"#
+            .as_bytes(),
+    )
+    .unwrap();
+    assert!(parser.next().is_none());
+
+    let empty = format!(
+        "{}<siteinfo />\n</mediawiki>\n",
+        &DUMP[..DUMP.find("<page>").unwrap()]
+    );
+    for capacity in 1..64 {
+        let mut parser = parse_mediawiki_dump::parse_skipping_siteinfo(
+            BufReader::with_capacity(capacity, empty.as_bytes()),
+        )
+        .unwrap();
+        assert!(parser.next().is_none());
+    }
+
+    let truncated = &empty[..empty.len() - 4];
+    for dump in &["<wiki><page></page></wiki>", "<mediawiki>", "", truncated] {
+        assert!(
+            parse_mediawiki_dump::parse_skipping_siteinfo(dump.as_bytes())
+                .is_err()
+        );
+    }
+}
+
+#[cfg(feature = "diff")]
+#[test]
+fn revision_diff() {
+    use parse_mediawiki_dump::DiffOp;
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page>
+            <ns>0</ns>
+            <title>alpha</title>
+            <revision><text/></revision>
+            <revision><text>a&#10;b&#10;c&#10;d</text></revision>
+            <revision><text>a&#10;c&#10;d&#10;e&#10;f</text></revision>
+        </page>
+    </mediawiki>"#;
+    let revisions = parse_mediawiki_dump::parse_revisions_flat(dump.as_bytes())
+        .map(|item| item.map(|(_, revision)| revision))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        revisions[1].diff(&revisions[0]),
+        [DiffOp::Insert {
+            new_index: 0,
+            len: 4
+        }]
+    );
+    assert_eq!(
+        revisions[2].diff(&revisions[1]),
+        [
+            DiffOp::Equal {
+                old_index: 0,
+                new_index: 0,
+                len: 1
+            },
+            DiffOp::Delete {
+                old_index: 1,
+                len: 1
+            },
+            DiffOp::Equal {
+                old_index: 2,
+                new_index: 1,
+                len: 2
+            },
+            DiffOp::Insert {
+                new_index: 3,
+                len: 2
+            },
+        ]
+    );
+    assert_eq!(
+        revisions[0].diff(&revisions[1]),
+        [DiffOp::Delete {
+            old_index: 0,
+            len: 4
+        }]
+    );
+    assert_eq!(revisions[0].diff(&revisions[0]), []);
+
+    // Blanking a long page, leaving one of its lines, takes memory linear in
+    // the number of lines.
+    let lines = 100_000;
+    let text = (0..lines)
+        .map(|line| format!("line {}", line))
+        .collect::<Vec<_>>()
+        .join("&#10;");
+    let dump = format!(
+        r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page>
+            <ns>0</ns>
+            <title>alpha</title>
+            <revision><text>{}</text></revision>
+            <revision><text>line 500</text></revision>
+        </page>
+    </mediawiki>"#,
+        text
+    );
+    let revisions = parse_mediawiki_dump::parse_revisions_flat(dump.as_bytes())
+        .map(|item| item.map(|(_, revision)| revision))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        revisions[1].diff(&revisions[0]),
+        [
+            DiffOp::Delete {
+                old_index: 0,
+                len: 500
+            },
+            DiffOp::Equal {
+                old_index: 500,
+                new_index: 0,
+                len: 1
+            },
+            DiffOp::Delete {
+                old_index: 501,
+                len: lines - 501
+            },
+        ]
+    );
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn record_batches() {
+    use arrow_array::{
+        cast::AsArray,
+        types::{Int32Type, UInt64Type},
+    };
+    let dump = DUMP.replacen("<ns>0</ns>", "<id>7</id><ns>0</ns>", 1);
+    let batches =
+        parse_mediawiki_dump::parse_record_batches(dump.as_bytes(), 10)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+    assert_eq!(batches.len(), 1);
+    let batch = &batches[0];
+    assert_eq!(batch.num_rows(), 2);
+    let page_ids = batch["page_id"].as_primitive::<UInt64Type>();
+    assert_eq!(page_ids.iter().collect::<Vec<_>>(), [Some(7), None]);
+    let namespaces = batch["namespace"].as_primitive::<Int32Type>();
+    assert_eq!(namespaces.values(), &[0, 1]);
+    let titles = batch["title"].as_string::<i32>();
+    assert_eq!(
+        titles.iter().collect::<Vec<_>>(),
+        [Some("alpha"), Some("epsilon")]
+    );
+    let redirect_titles = batch["redirect_title"].as_string::<i32>();
+    assert_eq!(
+        redirect_titles.iter().collect::<Vec<_>>(),
+        [None, Some("zeta")]
+    );
+    let text_lens = batch["text_len"].as_primitive::<UInt64Type>();
+    assert_eq!(text_lens.values(), &[5, 3]);
+    let texts = batch["text"].as_dictionary::<Int32Type>();
+    let values = texts.values().as_string::<i32>();
+    assert_eq!(
+        texts
+            .keys()
+            .values()
+            .iter()
+            .map(|key| values.value(*key as usize))
+            .collect::<Vec<_>>(),
+        ["delta", "eta"]
+    );
+
+    let mut batches = parse_mediawiki_dump::parse(dump.as_bytes())
+        .record_batches(10, parse_mediawiki_dump::TextColumn::Span);
+    assert_eq!(batches.schema().fields().len(), 6);
+    let batch = batches.next().unwrap().unwrap();
+    assert!(batches.next().is_none());
+    let offsets = batch["text_offset"].as_primitive::<UInt64Type>();
+    let text_lens = batch["text_len"].as_primitive::<UInt64Type>();
+    let texts = offsets
+        .values()
+        .iter()
+        .zip(text_lens.values())
+        .map(|(offset, len)| &dump[*offset as usize..(offset + len) as usize])
+        .collect::<Vec<_>>();
+    assert_eq!(texts, ["delta", "eta"]);
+}
+
+#[test]
+fn decode_entities() {
+    let dump = DUMP
+        .replace("alpha", "A&amp;B")
+        .replace("delta", "x &lt; y");
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .decode_entities(false)
+        .parse(dump.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.title, "A&amp;B");
+    assert_eq!(page.text, "x &lt; y");
+    assert_eq!(
+        parser.next().unwrap().unwrap().redirect_title.as_deref(),
+        Some("zeta")
+    );
+
+    let page = parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.title, "A&B");
+    assert_eq!(page.text, "x < y");
+}
+
+#[test]
+fn text_lines() {
+    let dump = DUMP.replace("delta", "alpha&#10;beta&#10;&#10;gamma");
+    let page = parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        page.text_lines().collect::<Vec<_>>(),
+        ["alpha", "beta", "", "gamma"]
+    );
+}
+
+#[test]
+fn site_info() {
+    use parse_mediawiki_dump::{DynamicNamespaces, NamespaceId};
+    let dump = DUMP.replacen(
+        "<page>",
+        r#"<siteinfo>
+        <sitename>Wikipedia</sitename>
+        <namespaces>
+            <namespace key="0" case="first-letter" />
+            <namespace key="4" case="first-letter">Wikipedia</namespace>
+            <namespace key="5" case="first-letter">Wikipedia talk</namespace>
+        </namespaces>
+        <namespacealiases>
+            <namespacealias key="4">WP</namespacealias>
+            <namespacealias key="5">WT</namespacealias>
+        </namespacealiases>
+    </siteinfo>
+    <page>"#,
+        1,
+    );
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .capture_site_info(true)
+        .parse(dump.as_bytes());
+    assert!(parser.site_info().is_none());
+    assert_eq!(parser.next().unwrap().unwrap().title, "alpha");
+    let site_info = parser.site_info().unwrap().clone();
+    assert_eq!(site_info.sitename.as_deref(), Some("Wikipedia"));
+    assert_eq!(site_info.namespaces.len(), 3);
+    assert_eq!(
+        site_info.aliases,
+        [(4, "WP".to_string()), (5, "WT".to_string())]
+    );
+    assert_eq!(parser.count(), 1);
+
+    let namespaces = DynamicNamespaces::new(&site_info);
+    assert_eq!(namespaces.id("WP"), Some(NamespaceId(4)));
+    assert_eq!(namespaces.id("wt"), Some(NamespaceId(5)));
+    assert_eq!(namespaces.id("Wikipedia_talk"), Some(NamespaceId(5)));
+    assert_eq!(namespaces.id(""), Some(NamespaceId(0)));
+    assert_eq!(namespaces.id("Talk"), None);
+    assert_eq!(namespaces.name(NamespaceId(4)), Some("Wikipedia"));
+
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    assert_eq!(parser.by_ref().count(), 2);
+    assert!(parser.site_info().is_none());
+}
+
+#[test]
+fn for_each_until() {
+    use std::ops::ControlFlow;
+    let mut titles = vec![];
+    parse_mediawiki_dump::parse(DUMP.as_bytes())
+        .for_each_until(|page| {
+            titles.push(page.title);
+            ControlFlow::Break(())
+        })
+        .unwrap();
+    assert_eq!(titles, ["alpha"]);
+
+    let mut count = 0;
+    parse_mediawiki_dump::parse(DUMP.as_bytes())
+        .for_each_until(|_| {
+            count += 1;
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    assert_eq!(count, 2);
+
+    let truncated = &DUMP[..DUMP.find("epsilon").unwrap()];
+    let mut titles = vec![];
+    assert!(matches!(
+        parse_mediawiki_dump::parse(truncated.as_bytes()).for_each_until(
+            |page| {
+                titles.push(page.title);
+                ControlFlow::Continue(())
+            }
+        ),
+        Err(parse_mediawiki_dump::Error::UnexpectedEof(_))
+    ));
+    assert_eq!(titles, ["alpha"]);
+
+    // The method of `Iterator` is not hidden.
+    let result = parse_mediawiki_dump::parse(DUMP.as_bytes()).try_for_each(
+        |page| -> Result<(), parse_mediawiki_dump::Error> {
+            page?;
+            Ok(())
+        },
+    );
+    assert!(result.is_ok());
+}
+
+#[test]
+fn next_text_into() {
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    let mut text = String::with_capacity(64);
+    let pointer = text.as_ptr();
+    let mut pages = vec![];
+    while let Some(page) = parser.next_text_into(&mut text) {
+        let page = page.unwrap();
+        assert!(page.text.is_empty());
+        pages.push((page.title, text.clone()));
+        assert_eq!(text.as_ptr(), pointer);
+        text.clear();
+    }
+    assert_eq!(
+        pages,
+        [
+            ("alpha".to_string(), "delta".to_string()),
+            ("epsilon".to_string(), "eta".to_string())
+        ]
+    );
+
+    // The text is appended, and the buffer survives skipped pages.
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .text_contains(b"eta")
+        .skip_unmatched(true)
+        .text_transform(|_, value| value.to_uppercase())
+        .parse(DUMP.as_bytes());
+    let mut text = String::with_capacity(64);
+    text.push_str("text: ");
+    let pointer = text.as_ptr();
+    let page = parser.next_text_into(&mut text).unwrap().unwrap();
+    assert_eq!(page.title, "EPSILON");
+    assert_eq!(text, "text: ETA");
+    assert_eq!(text.as_ptr(), pointer);
+    assert!(parser.next_text_into(&mut text).is_none());
+    assert_eq!(text, "text: ETA");
+}
+
+#[test]
+fn filter() {
+    use parse_mediawiki_dump::Filter;
+    let titles = |filter: Filter| {
+        parse_mediawiki_dump::ParserBuilder::new()
+            .filter(filter)
+            .parse(DUMP.as_bytes())
+            .map(|page| page.map(|page| page.title))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    };
+    assert_eq!(titles(Filter::default()), ["alpha", "epsilon"]);
+    assert_eq!(
+        titles(Filter {
+            namespaces: Some(vec![NamespaceId(1)]),
+            ..Default::default()
+        }),
+        ["epsilon"]
+    );
+    assert_eq!(
+        titles(Filter {
+            namespaces: Some(vec![NamespaceId(0), NamespaceId(1)]),
+            exclude_redirects: true,
+            ..Default::default()
+        }),
+        ["alpha"]
+    );
+    assert_eq!(
+        titles(Filter {
+            models: Some(vec!["gamma".to_string()]),
+            ..Default::default()
+        }),
+        ["alpha"]
+    );
+    assert!(titles(Filter {
+        namespaces: Some(vec![NamespaceId(1)]),
+        models: Some(vec!["gamma".to_string()]),
+        ..Default::default()
+    })
+    .is_empty());
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn filter_title_regex() {
+    let titles = parse_mediawiki_dump::ParserBuilder::new()
+        .filter(parse_mediawiki_dump::Filter {
+            exclude_redirects: false,
+            namespaces: Some(vec![NamespaceId(0), NamespaceId(1)]),
+            title_regex: Some(regex::Regex::new("^eps").unwrap()),
+            ..Default::default()
+        })
+        .parse(DUMP.as_bytes())
+        .map(|page| page.map(|page| page.title))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(titles, ["epsilon"]);
+}
+
+#[test]
+fn revision_child_orders() {
+    let children = [
+        "<format>beta</format>",
+        "<model>gamma</model>",
+        "<text>delta</text>",
+    ];
+    let orders = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+    for order in &orders {
+        let revision = order
+            .iter()
+            .map(|index| children[*index])
+            .collect::<String>();
+        let dump = format!(
+            r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+            <page>
+                <ns>0</ns>
+                <title>alpha</title>
+                <revision>{}</revision>
+            </page>
+        </mediawiki>"#,
+            revision
+        );
+        let pages = parse_mediawiki_dump::parse(dump.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(pages.len(), 1, "{}", revision);
+        let page = &pages[0];
+        assert_eq!(page.format.as_deref(), Some("beta"), "{}", revision);
+        assert_eq!(page.model.as_deref(), Some("gamma"), "{}", revision);
+        assert_eq!(page.text, "delta", "{}", revision);
+
+        let dump = dump.replace(
+            "</revision>",
+            "</revision><revision><text>eta</text></revision>",
+        );
+        assert!(
+            matches!(
+                parse_mediawiki_dump::parse(dump.as_bytes()).next(),
+                Some(Err(parse_mediawiki_dump::Error::NotSupported(_)))
+            ),
+            "{}",
+            revision
+        );
+    }
+
+    // A second `text` element within the same revision is a format error,
+    // not a second revision.
+    let dump = DUMP.replacen(
+        "<text>delta</text>",
+        "<text>delta</text><text>delta</text>",
+        1,
+    );
+    assert!(matches!(
+        parse_mediawiki_dump::parse(dump.as_bytes()).next(),
+        Some(Err(parse_mediawiki_dump::Error::Format(_)))
+    ));
+}
+
+#[test]
+fn estimated_total() {
+    let page = "<page><ns>0</ns><title>alpha</title>\
+        <revision><text>beta</text></revision></page>";
+    let dump = format!(
+        "<mediawiki xmlns=\"http://www.mediawiki.org/xml/export-0.10/\">{}</mediawiki>",
+        page.repeat(100)
+    );
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    assert_eq!(parser.estimated_total(dump.len() as u64), None);
+    for _ in 0..10 {
+        parser.next().unwrap().unwrap();
+    }
+    let estimate = parser.estimated_total(dump.len() as u64).unwrap();
+    assert!((80..=100).contains(&estimate), "{}", estimate);
+    parser.by_ref().for_each(drop);
+    let estimate = parser.estimated_total(dump.len() as u64).unwrap();
+    assert!((98..=100).contains(&estimate), "{}", estimate);
+    assert_eq!(parser.estimated_total(0), Some(0));
+}
+
+#[test]
+fn page_range() {
+    let page = |title: &str| {
+        format!(
+            "<page><ns>0</ns><title>{}</title>\
+            <revision><text>{}</text></revision></page>",
+            title, title
+        )
+    };
+    let dump = format!(
+        "<mediawiki xmlns=\"http://www.mediawiki.org/xml/export-0.10/\">\
+        {}{}{}</mediawiki>",
+        page("alpha"),
+        page("beta"),
+        page("gamma")
+    );
+    let titles = |range| {
+        parse_mediawiki_dump::ParserBuilder::new()
+            .page_range(range)
+            .parse(dump.as_bytes())
+            .map(|page| page.map(|page| page.title))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    };
+    assert_eq!(titles(1..2), ["beta"]);
+    assert_eq!(titles(0..2), ["alpha", "beta"]);
+    assert_eq!(titles(2..10), ["gamma"]);
+    assert!(titles(3..10).is_empty());
+    assert!(titles(1..1).is_empty());
+
+    // Parsing stops after the range, so the rest of the stream isn't read.
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .page_range(1..2)
+        .parse(dump.as_bytes());
+    assert_eq!(parser.next().unwrap().unwrap().title, "beta");
+    assert!(parser.next().is_none());
+    assert!(parser.stats().bytes_read < dump.find("gamma").unwrap());
+    parser.drain_to_end().unwrap();
+
+    // Pages skipped by other options are counted.
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .page_range(0..1)
+        .namespace_range(NamespaceId(1)..=NamespaceId(1))
+        .parse(DUMP.as_bytes());
+    assert!(parser.next().is_none());
+}
+
+#[test]
+fn sha1_bytes() {
+    let page = |sha1: &str| parse_mediawiki_dump::Page::<NamespaceId> {
+        sha1: Some(sha1.to_string()),
+        ..Default::default()
+    };
+    // The SHA-1 of "alpha".
+    let expected = [
+        0xbe, 0x76, 0x33, 0x1b, 0x95, 0xdf, 0xc3, 0x99, 0xcd, 0x77, 0x6d, 0x2f,
+        0xc6, 0x80, 0x21, 0xe0, 0xdb, 0x03, 0xcc, 0x4f,
+    ];
+    assert_eq!(page(&"0".repeat(31)).sha1_bytes(), Some([0; 20]));
+    let decoded = page("m8xh62unvuk757revyrugv0ebfr9v0v").sha1_bytes();
+    assert_eq!(decoded, Some(expected));
+    assert_eq!(
+        page("phoiac9h4m842xq45sp7s6u21eteeq1")
+            .sha1_bytes()
+            .unwrap()[..],
+        [
+            0xda, 0x39, 0xa3, 0xee, 0x5e, 0x6b, 0x4b, 0x0d, 0x32, 0x55, 0xbf,
+            0xef, 0x95, 0x60, 0x18, 0x90, 0xaf, 0xd8, 0x07, 0x09,
+        ]
+    );
+    // Without padding.
+    assert_eq!(page("1").sha1_bytes().unwrap()[19], 1);
+    // Not base 36, too long, or too large for 20 bytes.
+    assert_eq!(page("phoiac9h4m842xq45sp7s6u21eteeq!").sha1_bytes(), None);
+    assert_eq!(page(&"0".repeat(32)).sha1_bytes(), None);
+    assert_eq!(page(&"z".repeat(31)).sha1_bytes(), None);
+    assert_eq!(page("").sha1_bytes(), None);
+    let absent = parse_mediawiki_dump::Page::<NamespaceId>::default();
+    assert_eq!(absent.sha1_bytes(), None);
+}
+
+#[test]
+fn maybe_namespace() {
+    use parse_mediawiki_dump::MaybeNamespace;
+
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page><ns>1</ns><title>Talk:alpha</title><revision><text/></revision></page>
+    <page><ns>100</ns><title>Portal:beta</title><revision><text/></revision></page>
+</mediawiki>"#;
+    let namespaces = parse_mediawiki_dump::parse_with_namespace::<
+        _,
+        MaybeNamespace<Namespace>,
+    >(dump.as_bytes())
+    .map(|page| page.map(|page| page.namespace))
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+    assert_eq!(
+        namespaces,
+        [
+            MaybeNamespace::Known(Namespace::Talk, NamespaceId(1)),
+            MaybeNamespace::Unknown(NamespaceId(100)),
+        ]
+    );
+    assert_eq!(namespaces[0].known(), Some(&Namespace::Talk));
+    assert_eq!(namespaces[0].raw_id(), NamespaceId(1));
+    assert_eq!(namespaces[1].known(), None);
+    assert_eq!(namespaces[1].raw_id(), NamespaceId(100));
+
+    // Without the fallback, the unknown namespace is an error.
+    let mut parser = parse_mediawiki_dump::parse_with_namespace::<_, Namespace>(
+        dump.as_bytes(),
+    );
+    assert_eq!(parser.next().unwrap().unwrap().namespace, Namespace::Talk);
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::Namespace { .. }))
+    ));
+}
+
+#[test]
+fn on_namespace_change() {
+    let changes = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let parser = parse_mediawiki_dump::ParserBuilder::new()
+        .on_namespace_change({
+            let changes = changes.clone();
+            move |previous, namespace| {
+                changes.lock().unwrap().push((previous, namespace))
+            }
+        })
+        .parse(DUMP.as_bytes());
+    assert_eq!(parser.map(Result::unwrap).count(), 2);
+    assert_eq!(
+        *changes.lock().unwrap(),
+        [
+            (None, NamespaceId(0)),
+            (Some(NamespaceId(0)), NamespaceId(1))
+        ]
+    );
+
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+    <page><ns>0</ns><title>beta</title><revision><text/></revision></page>
+    <page><ns>10</ns><title>Template:gamma</title><revision><text/></revision></page>
+    <page><ns>10</ns><title>Template:delta</title><revision><text/></revision></page>
+</mediawiki>"#;
+    changes.lock().unwrap().clear();
+    let parser = parse_mediawiki_dump::ParserBuilder::new()
+        .on_namespace_change({
+            let changes = changes.clone();
+            move |previous, namespace| {
+                changes.lock().unwrap().push((previous, namespace))
+            }
+        })
+        .parse_meta(dump.as_bytes());
+    assert_eq!(parser.map(Result::unwrap).count(), 4);
+    assert_eq!(
+        *changes.lock().unwrap(),
+        [
+            (None, NamespaceId(0)),
+            (Some(NamespaceId(0)), NamespaceId(10))
+        ]
+    );
+}
+
+#[test]
+fn redirect_target_and_fragment() {
+    let page = |redirect_title: Option<&str>| parse_mediawiki_dump::Page::<
+        NamespaceId,
+    > {
+        redirect_title: redirect_title.map(String::from),
+        ..Default::default()
+    };
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <redirect title="Foo#History" />
+        <revision><text>#REDIRECT [[Foo#History]]</text></revision>
+    </page>
+</mediawiki>"#;
+    let parsed = parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        parsed.redirect_target_and_fragment(),
+        Some(("Foo", Some("History")))
+    );
+    assert_eq!(
+        page(Some("Foo")).redirect_target_and_fragment(),
+        Some(("Foo", None))
+    );
+    assert_eq!(
+        page(Some("Foo#")).redirect_target_and_fragment(),
+        Some(("Foo", Some("")))
+    );
+    assert_eq!(
+        page(Some("Foo#A#B")).redirect_target_and_fragment(),
+        Some(("Foo", Some("A#B")))
+    );
+    assert_eq!(
+        page(Some("C&#35; syntax#History")).redirect_target_and_fragment(),
+        Some(("C&#35; syntax", Some("History")))
+    );
+    assert_eq!(page(None).redirect_target_and_fragment(), None);
+}
+
+#[cfg(feature = "encoding_rs")]
+#[test]
+fn encoding() {
+    let dump = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>
+<mediawiki xmlns=\"http://www.mediawiki.org/xml/export-0.10/\">
+    <page>
+        <ns>0</ns>
+        <title>Caf\xe9</title>
+        <redirect title=\"\xc6sir\" caf\xe9=\"\xe9\" />
+        <revision><text>&#233; \xe9 &lt;<![CDATA[\xe9]]></text></revision>
+    </page>
+</mediawiki>";
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .encoding("iso-8859-1")
+        .parse(&dump[..])
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.title, "Caf\u{e9}");
+    assert_eq!(page.redirect_title.as_deref(), Some("\u{c6}sir"));
+    assert_eq!(
+        page.redirect_attributes,
+        [("caf\u{e9}".to_string(), "\u{e9}".to_string())]
+    );
+    assert_eq!(page.text, "\u{e9} \u{e9} <\u{e9}");
+
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .encoding("windows-1252")
+        .decode_entities(false)
+        .parse(&dump[..])
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.text, "&#233; \u{e9} &lt;\u{e9}");
+
+    // Without the encoding, the text is not valid UTF-8.
+    assert!(parse_mediawiki_dump::parse(&dump[..])
+        .next()
+        .unwrap()
+        .is_err());
+}
+
+#[test]
+fn last_namespace() {
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    assert_eq!(parser.last_namespace(), None);
+    parser.next().unwrap().unwrap();
+    assert_eq!(parser.last_namespace(), Some(NamespaceId(0)));
+    parser.next().unwrap().unwrap();
+    assert_eq!(parser.last_namespace(), Some(NamespaceId(1)));
+    assert!(parser.next().is_none());
+    assert_eq!(parser.last_namespace(), Some(NamespaceId(1)));
+    parser.reset_in_place(DUMP.as_bytes());
+    assert_eq!(parser.last_namespace(), None);
+
+    let mut parser = parse_mediawiki_dump::parse_meta(DUMP.as_bytes());
+    parser.by_ref().for_each(drop);
+    assert_eq!(parser.last_namespace(), Some(NamespaceId(1)));
+}
+
+#[test]
+fn declared_bytes_capacity() {
+    let dump = |bytes: &str| {
+        format!(
+            r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision><text{}>beta</text></revision>
+    </page>
+</mediawiki>"#,
+            bytes
+        )
+    };
+    let page = |dump: String| {
+        parse_mediawiki_dump::parse(dump.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap()
+    };
+    let declared = page(dump(r#" bytes="1000""#));
+    assert_eq!(declared.text, "beta");
+    assert!(declared.text.capacity() >= 1000);
+    assert!(page(dump("")).text.capacity() < 1000);
+
+    // A wrong length doesn't allocate too much.
+    let huge = page(dump(r#" bytes="1000000000000""#));
+    assert_eq!(huge.text, "beta");
+    assert!(huge.text.capacity() <= 1 << 26);
+
+    // The hint is reserved if it is greater.
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .text_hint(2000)
+        .parse(dump(r#" bytes="1000""#).as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert!(page.text.capacity() >= 2000);
+}
+
+#[test]
+fn normalize_newlines() {
+    let dump = "<mediawiki xmlns=\"http://www.mediawiki.org/xml/export-0.10/\">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision><text>a\r\nb\rc\nd\r\r\ne&#13;<![CDATA[\n]]>\u{e9}\r</text></revision>
+    </page>
+</mediawiki>";
+    let text = |enable| {
+        parse_mediawiki_dump::ParserBuilder::new()
+            .normalize_newlines(enable)
+            .parse(dump.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap()
+            .text
+    };
+    assert_eq!(text(true), "a\nb\nc\nd\n\ne\n\u{e9}\n");
+    assert_eq!(text(false), "a\r\nb\rc\nd\r\r\ne\r\n\u{e9}\r");
+}
+
+#[test]
+fn skip_current_page() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision><text>beta</text></revision>
+        <revision><text>gamma</text></revision>
+        <revision><text>delta</text></revision>
+    </page>
+    <page>
+        <ns>0</ns>
+        <title>epsilon</title>
+        <revision><text>zeta</text></revision>
+    </page>
+</mediawiki>"#;
+    let mut revisions =
+        parse_mediawiki_dump::parse_revisions_flat(dump.as_bytes());
+    // Not within a page yet.
+    assert!(matches!(
+        revisions.skip_current_page(),
+        Err(parse_mediawiki_dump::Error::NotInPage(_))
+    ));
+    let (page, revision) = revisions.next().unwrap().unwrap();
+    assert_eq!((&*page.title, &*revision.text), ("alpha", "beta"));
+    revisions.skip_current_page().unwrap();
+    let (page, revision) = revisions.next().unwrap().unwrap();
+    assert_eq!((&*page.title, &*revision.text), ("epsilon", "zeta"));
+    revisions.skip_current_page().unwrap();
+    assert!(revisions.next().is_none());
+    assert!(matches!(
+        revisions.skip_current_page(),
+        Err(parse_mediawiki_dump::Error::NotInPage(_))
+    ));
+
+    let truncated = &dump[..dump.find("gamma").unwrap()];
+    let mut revisions =
+        parse_mediawiki_dump::parse_revisions_flat(truncated.as_bytes());
+    revisions.next().unwrap().unwrap();
+    assert!(matches!(
+        revisions.skip_current_page(),
+        Err(parse_mediawiki_dump::Error::UnexpectedEof(_))
+    ));
+    assert!(revisions.next().is_none());
+}
+
+#[test]
+fn validate() {
+    let report = parse_mediawiki_dump::validate(DUMP.as_bytes()).unwrap();
+    assert_eq!(
+        report,
+        parse_mediawiki_dump::ValidationReport {
+            pages: 2,
+            schema_version: Some((0, 10)),
+            site_info: false,
+        }
+    );
+
+    let dump = r#"<?xml version="1.0"?>
+<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.11/">
+    <siteinfo><namespaces><namespace key="0" /></namespaces></siteinfo>
+    <page><ns>0</ns><title>alpha</title><revision><text/></revision></page>
+</mediawiki>
+<!-- end -->
+"#;
+    let report = parse_mediawiki_dump::validate(dump.as_bytes()).unwrap();
+    assert_eq!((report.pages, report.site_info), (1, true));
+    assert_eq!(report.schema_version, Some((0, 11)));
+
+    let report = parse_mediawiki_dump::validate(
+        &br#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/"/>"#[..],
+    )
+    .unwrap();
+    assert_eq!(report.pages, 0);
+
+    // Truncated.
+    let truncated = &DUMP[..DUMP.find("eta").unwrap()];
+    assert!(matches!(
+        parse_mediawiki_dump::validate(truncated.as_bytes()),
+        Err(parse_mediawiki_dump::Error::UnexpectedEof(_))
+    ));
+    // Mismatched end tag.
+    let mismatched = DUMP.replacen("</title>", "</titel>", 1);
+    assert!(matches!(
+        parse_mediawiki_dump::validate(mismatched.as_bytes()),
+        Err(parse_mediawiki_dump::Error::XmlReader(_))
+    ));
+    // Not a dump.
+    assert!(matches!(
+        parse_mediawiki_dump::validate(&b"<html></html>"[..]),
+        Err(parse_mediawiki_dump::Error::Format(0..=13))
+    ));
+    // Content after the root element.
+    let trailing = format!("{}<page/>", DUMP);
+    assert!(matches!(
+        parse_mediawiki_dump::validate(trailing.as_bytes()),
+        Err(parse_mediawiki_dump::Error::Format(_))
+    ));
+}
+
+#[test]
+fn next_batch_into() {
+    let page = |title: &str, text: &str| {
+        format!(
+            "<page><ns>0</ns><title>{}</title>\
+            <revision><text>{}</text></revision></page>",
+            title, text
+        )
+    };
+    let dump = format!(
+        "<mediawiki xmlns=\"http://www.mediawiki.org/xml/export-0.10/\">\
+        {}{}{}</mediawiki>",
+        page("alpha", &"beta".repeat(100)),
+        page("gamma", "delta"),
+        page("epsilon", "zeta")
+    );
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    let mut pages = vec![];
+    assert_eq!(parser.next_batch_into(&mut pages, 2).unwrap(), 2);
+    assert_eq!(pages.len(), 2);
+    assert_eq!(pages[0].text, "beta".repeat(100));
+    assert_eq!(pages[1].title, "gamma");
+    let capacity = pages.capacity();
+    let text = pages[0].text.as_ptr();
+
+    // The vector and the text of the first page are reused.
+    assert_eq!(parser.next_batch_into(&mut pages, 2).unwrap(), 1);
+    assert_eq!(pages.len(), 1);
+    assert_eq!((&*pages[0].title, &*pages[0].text), ("epsilon", "zeta"));
+    assert_eq!(pages.capacity(), capacity);
+    assert_eq!(pages[0].text.as_ptr(), text);
+
+    assert_eq!(parser.next_batch_into(&mut pages, 2).unwrap(), 0);
+    assert!(pages.is_empty());
+
+    // The pages before an error are kept.
+    let truncated = &dump[..dump.find("zeta").unwrap()];
+    let mut parser = parse_mediawiki_dump::parse(truncated.as_bytes());
+    assert!(matches!(
+        parser.next_batch_into(&mut pages, 3),
+        Err(parse_mediawiki_dump::Error::UnexpectedEof(_))
+    ));
+    assert_eq!(pages.len(), 2);
+}
+
+#[test]
+fn on_unknown_element() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/"
+    xmlns:ext="urn:ext">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <ext:rating value="&#233;&amp;" scale="5"><ext:x /></ext:rating>
+        <revision>
+            <origin>1</origin>
+            <text>beta</text>
+        </revision>
+    </page>
+</mediawiki>"#;
+    let elements = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let builder = || {
+        let elements = elements.clone();
+        parse_mediawiki_dump::ParserBuilder::new().on_unknown_element(
+            move |name, attributes, decoder| {
+                let attributes = attributes
+                    .iter()
+                    .map(|(key, value)| {
+                        (
+                            String::from_utf8(key.to_vec()).unwrap(),
+                            decoder.unescape(value).unwrap(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                assert!(decoder.position() > 0);
+                elements
+                    .lock()
+                    .unwrap()
+                    .push((name.to_string(), attributes));
+            },
+        )
+    };
+    let page = builder().parse(DUMP.as_bytes()).next().unwrap().unwrap();
+    assert_eq!(page.title, "alpha");
+    assert!(elements.lock().unwrap().is_empty());
+
+    let page = builder().parse(dump.as_bytes()).next().unwrap().unwrap();
+    assert_eq!(page.text, "beta");
+    assert_eq!(
+        *elements.lock().unwrap(),
+        [
+            (
+                "ext:rating".to_string(),
+                vec![
+                    ("value".to_string(), "\u{e9}&".to_string()),
+                    ("scale".to_string(), "5".to_string()),
+                ]
+            ),
+            ("origin".to_string(), vec![]),
+        ]
+    );
+
+    // Values are decoded as the fields are.
+    elements.lock().unwrap().clear();
+    builder()
+        .decode_entities(false)
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(elements.lock().unwrap()[0].1[0].1, "&#233;&amp;");
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn as_json() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>Q42</title>
+        <revision>
+            <model>wikibase-item</model>
+            <format>application/json</format>
+            <text>{"type":"item","id":"Q42","labels":{"en":{"language":"en","value":"Douglas Adams"}}}</text>
+        </revision>
+    </page>
+    <page>
+        <ns>120</ns>
+        <title>Property:P31</title>
+        <revision>
+            <model>wikibase-property</model>
+            <text>{"type":"property"</text>
+        </revision>
+    </page>
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision>
+            <model>json</model>
+            <text>{}</text>
+        </revision>
+    </page>
+</mediawiki>"#;
+    let pages = parse_mediawiki_dump::parse(dump.as_bytes())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let item = pages[0].as_json().unwrap();
+    assert_eq!(item["type"], "item");
+    assert_eq!(item["labels"]["en"]["value"], "Douglas Adams");
+    // Not valid JSON.
+    assert!(pages[1].as_json().is_none());
+    // Not a Wikibase model.
+    assert!(pages[2].as_json().is_none());
+    // No model.
+    let page = parse_mediawiki_dump::parse(DUMP.as_bytes())
+        .nth(1)
+        .unwrap()
+        .unwrap();
+    assert!(page.as_json().is_none());
+}
+
+#[cfg(feature = "bumpalo")]
+#[test]
+fn next_in() {
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    let mut bump = bumpalo::Bump::new();
+    let page = parser.next_in(&bump).unwrap().unwrap();
+    assert_eq!((page.title, page.text), ("alpha", "delta"));
+    assert_eq!(page.namespace, parse_mediawiki_dump::NamespaceId(0));
+    assert!(bump.allocated_bytes() > 0);
+    bump.reset();
+    let page = parser.next_in(&bump).unwrap().unwrap();
+    assert_eq!((page.title, page.text), ("epsilon", "eta"));
+    assert_eq!(page.redirect_title, Some("zeta"));
+    bump.reset();
+    assert!(parser.next_in(&bump).is_none());
+}
+
+#[test]
+fn detect_duplicate_titles() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page><ns>0</ns><title>alpha</title><revision><text>beta</text></revision></page>
+    <page><ns>0</ns><title>gamma</title><revision><text/></revision></page>
+    <page><ns>0</ns><title>alpha</title><revision><text>delta</text></revision></page>
+    <page><ns>0</ns><title>epsilon</title><revision><text/></revision></page>
+</mediawiki>"#;
+    let results = parse_mediawiki_dump::ParserBuilder::new()
+        .detect_duplicate_titles(true)
+        .parse(dump.as_bytes())
+        .map(|page| page.map(|page| page.title))
+        .collect::<Vec<_>>();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[1].as_ref().unwrap(), "gamma");
+    match &results[2] {
+        Err(parse_mediawiki_dump::Error::DuplicateTitle {
+            title,
+            position,
+        }) => {
+            assert_eq!(title, "alpha");
+            assert_eq!(
+                *position,
+                dump.find("delta").unwrap()
+                    + "delta</text></revision></page>".len()
+            );
+        }
+        result => panic!("{:?}", result),
+    }
+
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .detect_duplicate_titles(true)
+        .collect_warnings(true)
+        .parse(dump.as_bytes());
+    assert_eq!(parser.by_ref().map(Result::unwrap).count(), 4);
+    assert!(matches!(
+        parser.warnings(),
+        [parse_mediawiki_dump::Warning::DuplicateTitle { title, .. }]
+            if title == "alpha"
+    ));
+
+    // Disabled by default.
+    assert!(
+        parse_mediawiki_dump::parse(dump.as_bytes()).all(|page| page.is_ok())
+    );
+}
+
+#[test]
+fn for_each_parallel() {
+    let dump = format!(
+        r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">{}</mediawiki>"#,
+        (0..500)
+            .map(|index| format!(
+                "<page><ns>0</ns><title>{}</title><revision><text>{}</text></revision></page>",
+                index,
+                "a".repeat(index % 37),
+            ))
+            .collect::<String>()
+    );
+    let sequential = parse_mediawiki_dump::parse(dump.as_bytes())
+        .map(|page| page.unwrap().text.len())
+        .sum::<usize>();
+    let parallel = std::sync::atomic::AtomicUsize::new(0);
+    let pages = std::sync::atomic::AtomicUsize::new(0);
+    parse_mediawiki_dump::parse(dump.as_bytes())
+        .for_each_parallel(4, |page| {
+            use std::sync::atomic::Ordering;
+            parallel.fetch_add(page.text.len(), Ordering::Relaxed);
+            pages.fetch_add(1, Ordering::Relaxed);
+        })
+        .unwrap();
+    assert_eq!(parallel.into_inner(), sequential);
+    assert_eq!(pages.into_inner(), 500);
+
+    // The first error is returned after the pages before it are processed.
+    let truncated = &dump[..dump.find("<title>250</title>").unwrap()];
+    let pages = std::sync::atomic::AtomicUsize::new(0);
+    let result = parse_mediawiki_dump::parse(truncated.as_bytes())
+        .for_each_parallel(3, |_| {
+            pages.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        });
+    assert!(matches!(
+        result,
+        Err(parse_mediawiki_dump::Error::UnexpectedEof(_))
+    ));
+    assert_eq!(pages.into_inner(), 250);
+}
+
+#[test]
+fn empty_model_and_format() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision><model/><format></format><text>beta</text></revision>
+    </page>
+</mediawiki>"#;
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .allowed_models(&["wikitext"])
+        .allowed_formats(&["text/x-wiki"])
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!((page.model, page.format), (None, None));
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .normalize_model(true)
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!((page.model, page.raw_model), (None, None));
+    assert_eq!((page.format, page.raw_format), (None, None));
+
+    // Whitespace is not normalized into an empty model.
+    let dump = dump.replace("<model/>", "<model> </model>");
+    let page = parse_mediawiki_dump::ParserBuilder::new()
+        .normalize_model(true)
+        .parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!((page.model, page.raw_model), (None, None));
+
+    // An empty element still counts for the check for duplicate elements.
+    let dump = dump.replace("<model> </model>", "<model/><model>x</model>");
+    assert!(matches!(
+        parse_mediawiki_dump::parse(dump.as_bytes()).next(),
+        Some(Err(parse_mediawiki_dump::Error::Format(_)))
+    ));
+}
+
+#[test]
+fn text_attributes_on_other_elements() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision>
+            <contributor bytes="beta" sha1="gamma"><username>delta</username></contributor>
+            <comment bytes="7" location="epsilon">zeta</comment>
+            <extra bytes="eta" />
+            <text>theta</text>
+        </revision>
+    </page>
+</mediawiki>"#;
+    let page = parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.text, "theta");
+    assert_eq!(page.declared_bytes, None);
+    assert_eq!(page.declared_sha1, None);
+    assert_eq!(page.text_location, None);
+}
+
+#[test]
+fn text_reader() {
+    use std::io::{BufRead, Read};
+    let page = parse_mediawiki_dump::Page::<NamespaceId> {
+        text: "== Überschrift ==\nalpha βeta\n\ngamma 🎉\n".to_string(),
+        ..Default::default()
+    };
+    for chunk_size in 1..=8 {
+        let mut reader = page.text_reader(chunk_size);
+        let mut text = String::new();
+        loop {
+            let chunk = reader.fill_buf().unwrap();
+            if chunk.is_empty() {
+                break;
+            }
+            let chunk = std::str::from_utf8(chunk).unwrap();
+            assert!(chunk.len() <= chunk_size || chunk.chars().count() == 1);
+            text.push_str(chunk);
+            let length = chunk.len();
+            reader.consume(length);
+        }
+        assert_eq!(text, page.text);
+    }
+    // Reading into a buffer shorter than a character splits it.
+    let mut reader = page.text_reader(16);
+    let mut bytes = vec![];
+    let mut buffer = [0; 1];
+    while reader.read(&mut buffer).unwrap() > 0 {
+        bytes.push(buffer[0]);
+    }
+    assert_eq!(bytes, page.text.as_bytes());
+    let mut text = String::new();
+    page.text_reader(3).read_to_string(&mut text).unwrap();
+    assert_eq!(text, page.text);
+}
+
+#[test]
+fn next_title() {
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    assert!(matches!(
+        parser.skip_current_page(),
+        Err(parse_mediawiki_dump::Error::NotInPage(_))
+    ));
+    assert_eq!(parser.next_title().unwrap().unwrap(), "alpha");
+    parser.skip_current_page().unwrap();
+    assert!(matches!(
+        parser.skip_current_page(),
+        Err(parse_mediawiki_dump::Error::NotInPage(_))
+    ));
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.title, "epsilon");
+    assert_eq!(page.redirect_title, Some("zeta".to_string()));
+    assert!(parser.next().is_none());
+
+    // The rest of a page whose title was read is parsed by `next`.
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    assert_eq!(parser.next_title().unwrap().unwrap(), "alpha");
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!((&*page.title, &*page.text), ("alpha", "delta"));
+    assert_eq!(page.namespace, NamespaceId(0));
+
+    // Reading the next title skips the rest of the current page.
+    let mut parser =
+        parse_mediawiki_dump::ParserBuilder::new().parse(DUMP.as_bytes());
+    assert_eq!(parser.next_title().unwrap().unwrap(), "alpha");
+    assert_eq!(parser.next_title().unwrap().unwrap(), "epsilon");
+    assert!(parser.next_title().is_none());
+
+    // Pages outside the namespace range are skipped.
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .namespace_range(NamespaceId(1)..=NamespaceId(1))
+        .parse(DUMP.as_bytes());
+    assert_eq!(parser.next_title().unwrap().unwrap(), "epsilon");
+    parser.drain_to_end().unwrap();
+    assert!(parser.next().is_none());
+
+    // A page with more than one revision before its title is skipped as by
+    // `next` if enabled.
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <revision><text/></revision>
+        <revision><text>beta</text></revision>
+        <title>alpha</title>
+    </page>
+    <page><ns>0</ns><title>gamma</title><revision><text/></revision></page>
+</mediawiki>"#;
+    let namespaces = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .on_unsupported({
+            let namespaces = namespaces.clone();
+            move |page, _| namespaces.lock().unwrap().push(page.namespace)
+        })
+        .skip_unsupported(true)
+        .parse(dump.as_bytes());
+    assert_eq!(parser.next_title().unwrap().unwrap(), "gamma");
+    assert_eq!(*namespaces.lock().unwrap(), vec![Some(NamespaceId(0))]);
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    assert!(matches!(
+        parser.next_title(),
+        Some(Err(parse_mediawiki_dump::Error::NotSupported(_)))
+    ));
+}
+
+#[test]
+fn revision_minor_and_timestamp() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <ns>0</ns>
+        <title>alpha</title>
+        <revision>
+            <timestamp>2001-01-15T13:15:00Z</timestamp>
+            <minor/>
+            <text>beta</text>
+        </revision>
+        <revision>
+            <timestamp>2001-01-14T20:00:00Z</timestamp>
+            <text>gamma</text>
+        </revision>
+        <revision><text>delta</text></revision>
+    </page>
+</mediawiki>"#;
+    let mut revisions =
+        parse_mediawiki_dump::parse_revisions_flat(dump.as_bytes())
+            .map(|item| item.unwrap().1)
+            .collect::<Vec<_>>();
+    assert_eq!(
+        revisions
+            .iter()
+            .map(|revision| revision.is_minor())
+            .collect::<Vec<_>>(),
+        [true, false, false]
+    );
+    assert_eq!(
+        revisions[0].raw_timestamp.as_deref(),
+        Some("2001-01-15T13:15:00Z")
+    );
+    assert_eq!(revisions[2].raw_timestamp, None);
+    #[cfg(feature = "chrono")]
+    {
+        use chrono::TimeZone;
+        assert_eq!(
+            revisions[0].timestamp(),
+            Some(
+                chrono::Utc
+                    .with_ymd_and_hms(2001, 1, 15, 13, 15, 0)
+                    .unwrap()
+            )
+        );
+        assert_eq!(revisions[2].timestamp(), None);
+    }
+    revisions.sort_by(|a, b| a.cmp_timestamp(b));
+    assert_eq!(
+        revisions
+            .iter()
+            .map(|revision| &*revision.text)
+            .collect::<Vec<_>>(),
+        ["delta", "gamma", "beta"]
+    );
+    let heap = revisions
+        .into_iter()
+        .map(parse_mediawiki_dump::ByTimestamp)
+        .collect::<std::collections::BinaryHeap<_>>();
+    assert_eq!(heap.peek().unwrap().0.text, "beta");
+}
+
+#[test]
+fn cancellation() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .cancellation(cancelled.clone())
+        .parse(DUMP.as_bytes());
+    assert_eq!(parser.next().unwrap().unwrap().title, "alpha");
+    cancelled.store(true, Ordering::Relaxed);
+    let position = DUMP.find("</page>").unwrap() + "</page>".len();
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::Cancelled { position: p }))
+            if p == position
+    ));
+    assert!(parser.next().is_none());
+    cancelled.store(false, Ordering::Relaxed);
+    assert!(parser.next().is_none());
+
+    let cancelled = Arc::new(AtomicBool::new(true));
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .cancellation(cancelled)
+        .parse(DUMP.as_bytes());
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::Cancelled { position: 0 }))
+    ));
+}
+
+#[test]
+fn capture_threads() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page>
+            <ns>90</ns>
+            <title>Thread:Talk:Alpha/Beta</title>
+            <revision><text>gamma</text></revision>
+            <DiscussionThreading>
+                <ThreadSubject>Beta</ThreadSubject>
+                <ThreadParent>1</ThreadParent>
+                <ThreadAncestor>1</ThreadAncestor>
+                <ThreadPage>Talk:Alpha</ThreadPage>
+                <ThreadID>2</ThreadID>
+                <ThreadAuthor>Delta</ThreadAuthor>
+                <ThreadEditStatus>Unedited</ThreadEditStatus>
+                <ThreadType>Normal</ThreadType>
+                <ThreadSignature>[[User:Delta|Delta]]</ThreadSignature>
+                <ThreadUnknown>epsilon</ThreadUnknown>
+            </DiscussionThreading>
+        </page>
+        <page>
+            <ns>1</ns>
+            <title>Talk:Alpha</title>
+            <revision><text/></revision>
+        </page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .capture_threads(true)
+        .parse(dump.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.text, "gamma");
+    assert_eq!(
+        page.thread,
+        Some(parse_mediawiki_dump::Thread {
+            ancestor: Some(1),
+            author: Some("Delta".to_string()),
+            edit_status: Some("Unedited".to_string()),
+            id: Some(2),
+            page: Some("Talk:Alpha".to_string()),
+            parent: Some(1),
+            signature: Some("[[User:Delta|Delta]]".to_string()),
+            subject: Some("Beta".to_string()),
+            thread_type: Some("Normal".to_string()),
+        })
+    );
+    assert_eq!(parser.next().unwrap().unwrap().thread, None);
+    assert!(parser.next().is_none());
+
+    let page = parse_mediawiki_dump::parse(dump.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(page.thread, None);
+}
+
+#[test]
+fn collect_warnings() {
+    use parse_mediawiki_dump::{PartialPage, Warning};
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.99/">
+        <page><title>alpha</title><revision><text/></revision></page>
+        <page>
+            <ns>0</ns>
+            <title>beta</title>
+            <revision><text/></revision>
+            <revision><text/></revision>
+        </page>
+        <page><ns>1</ns><title>gamma</title><revision><text/></revision></page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .accept_any_schema()
+        .default_namespace(NamespaceId(0))
+        .skip_unsupported(true)
+        .collect_warnings(true)
+        .parse(dump.as_bytes());
+    let titles = parser
+        .by_ref()
+        .map(|page| page.map(|page| page.title))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(titles, ["alpha", "gamma"]);
+    let warnings = parser.take_warnings();
+    assert!(parser.warnings().is_empty());
+    match &*warnings {
+        [Warning::UnknownSchema {
+            namespace,
+            position: schema_position,
+        }, Warning::DefaultNamespace {
+            title,
+            position: namespace_position,
+        }, Warning::SkippedUnsupported {
+            page,
+            position: skipped_position,
+        }] => {
+            assert_eq!(namespace, "http://www.mediawiki.org/xml/export-0.99/");
+            assert_eq!(*schema_position, dump.find("<page>").unwrap() - 9);
+            assert_eq!(title.as_deref(), Some("alpha"));
+            assert_eq!(
+                *namespace_position,
+                dump.find("</page>").unwrap() + "</page>".len()
+            );
+            assert_eq!(
+                *page,
+                PartialPage {
+                    namespace: Some(NamespaceId(0)),
+                    title: Some("beta".to_string()),
+                }
+            );
+            assert!(*skipped_position > *namespace_position);
+        }
+        warnings => panic!("{:?}", warnings),
+    }
+
+    let dump = dump.replace("0.99", "0.10");
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .default_namespace(NamespaceId(0))
+        .parse(dump.as_bytes());
+    assert!(parser.next().unwrap().is_ok());
+    assert!(parser.warnings().is_empty());
+}
+
+#[test]
+fn namespace_all() {
+    assert_eq!(Namespace::all().len(), 18);
+    assert_eq!(Namespace::all()[0], Namespace::Media);
+    assert_eq!(Namespace::all()[17], Namespace::CategoryTalk);
+    assert!(Namespace::iter().eq(Namespace::all().iter().copied()));
+    for namespace in Namespace::iter() {
+        assert_eq!(
+            <Namespace as parse_mediawiki_dump::FromNamespaceId>::from_namespace_id(
+                namespace.id()
+            ),
+            Some(namespace)
+        );
+    }
+    assert_eq!(Namespace::Special.id(), NamespaceId(-1));
+}
+
+#[test]
+fn allowed_models() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+        <page>
+            <ns>0</ns>
+            <title>alpha</title>
+            <revision>
+                <model>wikitext</model>
+                <format>text/x-wiki</format>
+                <text/>
+            </revision>
+        </page>
+        <page>
+            <ns>8</ns>
+            <title>MediaWiki:Common.css</title>
+            <revision>
+                <model>css</model>
+                <format>text/css</format>
+                <text/>
+            </revision>
+        </page>
+    </mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .allowed_models(&["wikitext"])
+        .parse(dump.as_bytes());
+    assert_eq!(parser.next().unwrap().unwrap().title, "alpha");
+    match parser.next() {
+        Some(Err(parse_mediawiki_dump::Error::UnexpectedModel {
+            model,
+            position,
+        })) => {
+            assert_eq!(model, "css");
+            assert_eq!(
+                position,
+                dump.find("css</model>").unwrap() + "css</model>".len()
+            );
+        }
+        result => panic!("{:?}", result),
+    }
+
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .allowed_formats(&["text/x-wiki"])
+        .parse(dump.as_bytes());
+    assert!(parser.next().unwrap().is_ok());
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::UnexpectedFormat { format, .. }))
+            if format == "text/css"
+    ));
+
+    let dump = dump.replace("<model>css</model>", "<model> CSS </model>");
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .allowed_models(&["wikitext", "css"])
+        .normalize_model(true)
+        .parse(dump.as_bytes());
+    assert!(parser.next().unwrap().is_ok());
+    assert_eq!(
+        parser.next().unwrap().unwrap().model.as_deref(),
+        Some("css")
+    );
+
+    assert_eq!(parse_mediawiki_dump::parse(dump.as_bytes()).count(), 2);
+}
+
+#[test]
+fn fused_after_error() {
+    let dump = DUMP.replacen("<ns>0</ns>", "<ns>zero</ns>", 1);
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::InvalidNamespaceId { .. }))
+    ));
+    assert!(parser.next().is_none());
+    assert!(parser.next().is_none());
+
+    let mut parser = parse_mediawiki_dump::parse_meta(dump.as_bytes());
+    assert!(parser.next().unwrap().is_err());
+    assert!(parser.next().is_none());
+
+    let mut revisions =
+        parse_mediawiki_dump::parse_revisions_flat(dump.as_bytes());
+    assert!(revisions.next().unwrap().is_err());
+    assert!(revisions.next().is_none());
+
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .recover_from_errors(true)
+        .parse(dump.as_bytes());
+    assert!(parser.next().unwrap().is_err());
+    assert_eq!(parser.next().unwrap().unwrap().title, "epsilon");
+    assert!(parser.next().is_none());
+
+    let truncated = &DUMP[..DUMP.find("</page>").unwrap()];
+    let mut parser = parse_mediawiki_dump::parse(truncated.as_bytes());
+    assert!(matches!(
+        parser.next(),
+        Some(Err(parse_mediawiki_dump::Error::UnexpectedEof(_)))
+    ));
+    assert!(parser.next().is_none());
+}
+
+#[test]
+fn default_sort() {
+    let page = |text: &str| parse_mediawiki_dump::Page::<NamespaceId> {
+        text: text.to_string(),
+        ..Default::default()
+    };
+    assert_eq!(page("").default_sort(), None);
+    assert_eq!(page("{{Template}} [[Category:Alpha]]").default_sort(), None);
+    assert_eq!(page("{{DEFAULTSORT:Beta}}").default_sort(), Some("Beta"));
+    assert_eq!(
+        page("{{ DEFAULTSORTKEY: Gamma, Delta }}").default_sort(),
+        Some("Gamma, Delta")
+    );
+    assert_eq!(
+        page("{{DEFAULTSORT:Epsilon}}{{DEFAULTCATEGORYSORT:Zeta|noerror}}")
+            .default_sort(),
+        Some("Zeta")
+    );
+    assert_eq!(
+        page("{{DEFAULTSORT:Eta}}{{DEFAULTSORT:Theta|noreplace}}")
+            .default_sort(),
+        Some("Eta")
+    );
+    assert_eq!(page("{{DEFAULTSORT:{{PAGENAME}}}}").default_sort(), None);
+    assert_eq!(page("{{DEFAULTSORT:}}").default_sort(), None);
+    assert_eq!(page("{{DEFAULTSORT:Iota").default_sort(), None);
+    assert_eq!(page("{{defaultsort:Kappa}}").default_sort(), None);
+}
+
+#[test]
+fn is_binary_content() {
+    let dump = DUMP
+        .replace(
+            "<format>beta</format>",
+            "<format>application/octet-stream</format>",
+        )
+        .replace("<text>delta</text>", "<text>AAEC</text>");
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(
+        page.content_format(),
+        Some(parse_mediawiki_dump::ContentFormat::OctetStream)
+    );
+    assert!(page.is_binary_content());
+    assert_eq!(page.text, "AAEC");
+    let page = parser.next().unwrap().unwrap();
+    assert!(!page.is_binary_content());
+    assert!(!parse_mediawiki_dump::parse(DUMP.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap()
+        .is_binary_content());
+}
+
+#[test]
+fn text_contains() {
+    let titles = |builder: parse_mediawiki_dump::ParserBuilder| {
+        builder
+            .parse(DUMP.as_bytes())
+            .map(|page| page.map(|page| (page.title, page.text)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    };
+    assert_eq!(
+        titles(
+            parse_mediawiki_dump::ParserBuilder::new()
+                .text_contains(b"delta")
+                .skip_unmatched(true)
+        ),
+        [("alpha".to_string(), "delta".to_string())]
+    );
+    assert_eq!(
+        titles(
+            parse_mediawiki_dump::ParserBuilder::new().text_contains(b"elt")
+        ),
+        [
+            ("alpha".to_string(), "delta".to_string()),
+            ("epsilon".to_string(), String::new())
+        ]
+    );
+    assert_eq!(
+        titles(parse_mediawiki_dump::ParserBuilder::new().text_contains(b"")),
+        [
+            ("alpha".to_string(), "delta".to_string()),
+            ("epsilon".to_string(), "eta".to_string())
+        ]
+    );
+
+    // Matches spanning chunks of text, entities and CDATA sections.
+    let dump = DUMP.replace(
+        "<text>delta</text>",
+        "<text>a&amp;b<![CDATA[c]]>d<![CDATA[e]]>f</text>",
+    );
+    for (needle, found) in &[
+        (&b"&bcdef"[..], true),
+        (b"a&b", true),
+        (b"cde", true),
+        (b"bcdf", false),
+        (b"&amp;", false),
+    ] {
+        let page = parse_mediawiki_dump::ParserBuilder::new()
+            .text_contains(needle)
+            .parse(dump.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(!page.text.is_empty(), *found, "{:?}", needle);
+    }
+}
+
+#[test]
+fn xmlns() {
+    let dump = DUMP.replace(
+        "http://www.mediawiki.org/xml/export-0.10/",
+        "https://wiki.example/xml/export/",
+    );
+    assert!(matches!(
+        parse_mediawiki_dump::parse(dump.as_bytes()).next(),
+        Some(Err(parse_mediawiki_dump::Error::Format(_)))
+    ));
+    let mut parser = parse_mediawiki_dump::ParserBuilder::new()
+        .xmlns("https://wiki.example/xml/export/")
+        .collect_warnings(true)
+        .parse(dump.as_bytes());
+    let titles = parser
+        .by_ref()
+        .map(|page| page.map(|page| page.title))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(titles, ["alpha", "epsilon"]);
+    assert!(parser.warnings().is_empty());
+
+    let titles = parse_mediawiki_dump::ParserBuilder::new()
+        .xmlns("https://wiki.example/xml/export/")
+        .parse(DUMP.as_bytes())
+        .map(|page| page.map(|page| page.title))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(titles, ["alpha", "epsilon"]);
+}
+
+#[test]
+fn stats() {
+    let mut parser = parse_mediawiki_dump::parse(DUMP.as_bytes());
+    assert_eq!(parser.stats(), parse_mediawiki_dump::ParserStats::default());
+    let texts = parser
+        .by_ref()
+        .map(|page| page.unwrap().text.len())
+        .sum::<usize>();
+    assert_eq!(
+        parser.stats(),
+        parse_mediawiki_dump::ParserStats {
+            bytes_read: DUMP.len(),
+            pages: 2,
+            redirects: 1,
+            text_bytes: "delta".len() + "eta".len(),
+        }
+    );
+    assert_eq!(parser.stats().text_bytes, texts);
+    parser.reset_in_place(DUMP.as_bytes());
+    assert_eq!(parser.stats(), parse_mediawiki_dump::ParserStats::default());
+
+    let mut parser = parse_mediawiki_dump::parse_meta(DUMP.as_bytes());
+    parser.by_ref().for_each(drop);
+    let stats = parser.stats();
+    assert_eq!((stats.pages, stats.redirects, stats.text_bytes), (2, 1, 0));
+}
+
+#[test]
+fn revision_first() {
+    let dump = r#"<mediawiki xmlns="http://www.mediawiki.org/xml/export-0.10/">
+    <page>
+        <revision>
+            <format>beta</format>
+            <model>gamma</model>
+            <text>delta</text>
+        </revision>
+        <redirect title="zeta" />
+        <title>alpha</title>
+        <ns>1</ns>
+        <id>2</id>
+    </page>
+    <page>
+        <revision><text>eta</text></revision>
+        <ns>0</ns>
+        <title>epsilon</title>
+    </page>
+</mediawiki>"#;
+    let mut parser = parse_mediawiki_dump::parse(dump.as_bytes());
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!(page.namespace, NamespaceId(1));
+    assert_eq!(page.title, "alpha");
+    assert_eq!(page.format.as_deref(), Some("beta"));
+    assert_eq!(page.model.as_deref(), Some("gamma"));
+    assert_eq!(page.text, "delta");
+    assert_eq!(page.redirect_title.as_deref(), Some("zeta"));
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!((page.namespace, &*page.title), (NamespaceId(0), "epsilon"));
+    assert_eq!(page.text, "eta");
+    assert!(parser.next().is_none());
+
+    let mut parser =
+        parse_mediawiki_dump::parse_meta(dump.as_bytes()).map(Result::unwrap);
+    let page = parser.next().unwrap();
+    assert_eq!((page.page_id, &*page.title), (Some(2), "alpha"));
+    assert_eq!(parser.next().unwrap().title, "epsilon");
+
+    // Revisions are returned as they are read, so the title and namespace
+    // must come first.
+    assert!(matches!(
+        parse_mediawiki_dump::parse_revisions_flat(dump.as_bytes()).next(),
+        Some(Err(parse_mediawiki_dump::Error::Format(_)))
+    ));
+
+    let dump = dump.replacen(
+        "<redirect",
+        "<revision><text>theta</text></revision><redirect",
+        1,
+    );
+    assert!(matches!(
+        parse_mediawiki_dump::parse(dump.as_bytes()).next(),
+        Some(Err(parse_mediawiki_dump::Error::NotSupported(_)))
+    ));
+    let titles = parse_mediawiki_dump::ParserBuilder::new()
+        .skip_unsupported(true)
+        .parse(dump.as_bytes())
+        .map(|page| page.unwrap().title)
+        .collect::<Vec<_>>();
+    assert_eq!(titles, ["epsilon"]);
+}
+
+#[test]
+fn parse_with_namespace_fn() {
+    #[derive(Debug, PartialEq)]
+    struct Main;
+    let mut parser =
+        parse_mediawiki_dump::parse_with_namespace_fn(DUMP.as_bytes(), |id| {
+            if id == NamespaceId(0) {
+                Some(Main)
+            } else {
+                None
+            }
+        });
+    let page = parser.next().unwrap().unwrap();
+    assert_eq!((page.namespace, &*page.title), (Main, "alpha"));
+    assert_eq!(page.text, "delta");
+    match parser.next() {
+        Some(Err(parse_mediawiki_dump::Error::Namespace { id, position })) => {
+            assert_eq!(id, NamespaceId(1));
+            assert_eq!(position, DUMP.rfind("</page>").unwrap() + 7);
+        }
+        result => panic!("{:?}", result),
+    }
+    assert!(parser.next().is_none());
+    assert_eq!(parser.parser().stats().pages, 2);
+}
+
+#[test]
+fn strip_html_comments() {
+    let text = |text: &str| {
+        let dump = DUMP.replace("<text>delta</text>", text);
+        parse_mediawiki_dump::ParserBuilder::new()
+            .strip_html_comments(true)
+            .parse(dump.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap()
+            .text
+    };
+    assert_eq!(text("<text>delta</text>"), "delta");
+    assert_eq!(text("<text>a&lt;!-- b --&gt;c&lt;!----&gt;d</text>"), "acd");
+    // A comment spanning chunks of text and CDATA sections.
+    assert_eq!(text("<text>a&lt;!-- b<![CDATA[ c -]]>-&gt;d</text>"), "ad");
+    assert_eq!(text("<text>a&lt;!--&gt;b--&gt;c</text>"), "ac");
+    assert_eq!(text("<text>a&lt;!-- b</text>"), "a");
+    assert_eq!(text("<text>a--&gt;b</text>"), "a-->b");
+
+    let page = parse_mediawiki_dump::parse(
+        DUMP.replace("delta", "a&lt;!-- b --&gt;").as_bytes(),
+    )
+    .next()
+    .unwrap()
+    .unwrap();
+    assert_eq!(page.text, "a<!-- b -->");
+}
+
+#[test]
+fn dump_into_iterator() {
+    let mut titles = vec![];
+    for page in parse_mediawiki_dump::Dump::new(DUMP.as_bytes()) {
+        let page = page.unwrap();
+        titles.push((page.namespace, page.title));
+    }
+    assert_eq!(
+        titles,
+        [
+            (NamespaceId(0), "alpha".to_string()),
+            (NamespaceId(1), "epsilon".to_string())
+        ]
+    );
+
+    let dump = parse_mediawiki_dump::Dump::new(DUMP.as_bytes())
+        .options(
+            parse_mediawiki_dump::ParserBuilder::new()
+                .namespace_range(NamespaceId(1)..=NamespaceId(1)),
+        )
+        .namespace::<Namespace>();
+    let namespaces = dump
+        .into_iter()
+        .map(|page| page.unwrap().namespace)
+        .collect::<Vec<_>>();
+    assert_eq!(namespaces, [Namespace::Talk]);
+}
+
+#[test]
+fn record_empty_text_element() {
+    let dump = DUMP
+        .replace("<text>delta</text>", "<text/>")
+        .replace("<text>eta</text>", "<text></text>");
+    let forms = |builder: parse_mediawiki_dump::ParserBuilder| {
+        builder
+            .parse(dump.as_bytes())
+            .map(|page| {
+                let page = page.unwrap();
+                assert_eq!(page.text, "");
+                page.text_was_empty_element
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        forms(
+            parse_mediawiki_dump::ParserBuilder::new()
+                .record_empty_text_element(true)
+        ),
+        [true, false]
+    );
+    assert_eq!(
+        forms(
+            parse_mediawiki_dump::ParserBuilder::new()
+                .record_empty_text_element(true)
+                .text_spans(true)
+        ),
+        [true, false]
+    );
+    assert_eq!(
+        forms(parse_mediawiki_dump::ParserBuilder::new()),
+        [false, false]
+    );
+    // Not recorded unless enabled, even if empty elements aren't expanded.
+    assert_eq!(
+        forms(
+            parse_mediawiki_dump::ParserBuilder::new()
+                .expand_empty_elements(false)
+        ),
+        [false, false]
+    );
+    let revisions = parse_mediawiki_dump::ParserBuilder::new()
+        .record_empty_text_element(true)
+        .parse(dump.as_bytes())
+        .revisions_flat()
+        .map(|item| item.unwrap().1.text_was_empty_element)
+        .collect::<Vec<_>>();
+    assert_eq!(revisions, [true, false]);
+}